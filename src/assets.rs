@@ -1,9 +1,49 @@
+use std::fmt;
+
 use apollo_cw_asset::{Asset, AssetInfo, AssetList};
 use cosmwasm_std::{
-    attr, to_binary, Addr, Api, Coin, CosmosMsg, Env, Event, MessageInfo, Response, StdError,
-    StdResult, WasmMsg,
+    attr, to_json_binary, Addr, Api, Coin, CosmosMsg, Decimal, Env, Event, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
 };
-use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg};
+use thiserror::Error;
+
+#[cfg(feature = "cw-storage-plus")]
+mod escrow;
+mod policy;
+mod pool;
+#[cfg(feature = "cw-storage-plus")]
+mod storage;
+
+#[cfg(feature = "cw-storage-plus")]
+pub use escrow::*;
+pub use policy::*;
+pub use pool::*;
+#[cfg(feature = "cw-storage-plus")]
+pub use storage::*;
+
+/// Validate the address of a `Cw20Coin`.
+///
+/// ### Returns
+/// Returns a `Cw20CoinVerified` with the validated address.
+pub fn check_cw20_coin(api: &dyn Api, cw20: &Cw20Coin) -> StdResult<Cw20CoinVerified> {
+    Ok(Cw20CoinVerified {
+        address: api.addr_validate(&cw20.address)?,
+        amount: cw20.amount,
+    })
+}
+
+/// Validate the addresses of a list of `Cw20Coin`s.
+///
+/// ### Returns
+/// Returns a `Vec<Cw20CoinVerified>` with the validated addresses, or an error
+/// if any of the addresses are invalid.
+pub fn check_cw20_coins(api: &dyn Api, cw20s: &[Cw20Coin]) -> StdResult<Vec<Cw20CoinVerified>> {
+    cw20s
+        .iter()
+        .map(|cw20| check_cw20_coin(api, cw20))
+        .collect()
+}
 
 /// Create an AssetList from a `Vec<Coin>` and an optional `Vec<Cw20Coin>`.
 /// Removes duplicates from each of the inputs.
@@ -11,6 +51,18 @@ pub fn to_asset_list(
     api: &dyn Api,
     coins: Option<&Vec<Coin>>,
     cw20s: Option<&Vec<Cw20Coin>>,
+) -> StdResult<AssetList> {
+    let cw20s = cw20s
+        .map(|cw20s| check_cw20_coins(api, cw20s))
+        .transpose()?;
+    to_asset_list_verified(coins, cw20s.as_ref())
+}
+
+/// Create an AssetList from a `Vec<Coin>` and an optional
+/// `Vec<Cw20CoinVerified>`. Removes duplicates from each of the inputs.
+pub fn to_asset_list_verified(
+    coins: Option<&Vec<Coin>>,
+    cw20s: Option<&Vec<Cw20CoinVerified>>,
 ) -> StdResult<AssetList> {
     let mut assets = AssetList::new();
 
@@ -22,10 +74,7 @@ pub fn to_asset_list(
 
     if let Some(cw20s) = cw20s {
         for cw20 in cw20s {
-            assets.add(&Asset::new(
-                AssetInfo::Cw20(api.addr_validate(&cw20.address)?),
-                cw20.amount,
-            ))?;
+            assets.add(&cw20.clone().into())?;
         }
     }
     Ok(assets)
@@ -33,6 +82,22 @@ pub fn to_asset_list(
 
 /// Converts an `AssetList` into a `Vec<Coin>` and a `Vec<Cw20Coin>`.
 pub fn separate_natives_and_cw20s(assets: &AssetList) -> (Vec<Coin>, Vec<Cw20Coin>) {
+    let (coins, cw20s) = separate_natives_and_cw20s_verified(assets);
+    let cw20s = cw20s
+        .into_iter()
+        .map(|cw20| Cw20Coin {
+            address: cw20.address.to_string(),
+            amount: cw20.amount,
+        })
+        .collect();
+
+    (coins, cw20s)
+}
+
+/// Converts an `AssetList` into a `Vec<Coin>` and a `Vec<Cw20CoinVerified>`.
+pub fn separate_natives_and_cw20s_verified(
+    assets: &AssetList,
+) -> (Vec<Coin>, Vec<Cw20CoinVerified>) {
     let mut coins = vec![];
     let mut cw20s = vec![];
 
@@ -45,8 +110,8 @@ pub fn separate_natives_and_cw20s(assets: &AssetList) -> (Vec<Coin>, Vec<Cw20Coi
                 });
             }
             AssetInfo::Cw20(addr) => {
-                cw20s.push(Cw20Coin {
-                    address: addr.to_string(),
+                cw20s.push(Cw20CoinVerified {
+                    address: addr.clone(),
                     amount: asset.amount,
                 });
             }
@@ -93,7 +158,7 @@ pub fn assert_native_tokens_received(
 ) -> StdResult<Vec<Coin>> {
     let coins = assert_only_native_coins(assets)?;
     for coin in &coins {
-        if !info.funds.contains(&coin) {
+        if !info.funds.contains(coin) {
             return Err(StdError::generic_err(format!(
                 "Assert native token received failed for asset: {}",
                 coin
@@ -158,7 +223,7 @@ pub fn receive_assets(info: &MessageInfo, env: &Env, assets: &AssetList) -> StdR
         .map(|asset| receive_asset_msg(info, env, asset))
         .collect::<StdResult<Vec<Option<_>>>>()?
         .into_iter()
-        .filter_map(|msg| msg)
+        .flatten()
         .collect::<Vec<_>>();
 
     Ok(Response::new().add_messages(msgs).add_event(event))
@@ -226,13 +291,13 @@ pub fn increase_allowance_msgs(
     assets: &AssetList,
     recipient: Addr,
 ) -> StdResult<(Vec<CosmosMsg>, Vec<Coin>)> {
-    let (funds, cw20s) = separate_natives_and_cw20s(assets);
+    let (funds, cw20s) = separate_natives_and_cw20s_verified(assets);
     let msgs: Vec<CosmosMsg> = cw20s
         .into_iter()
         .map(|x| {
             Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: x.address,
-                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                contract_addr: x.address.into(),
+                msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
                     spender: recipient.to_string(),
                     amount: x.amount,
                     expires: Some(cw20::Expiration::AtHeight(env.block.height + 1)),
@@ -244,6 +309,113 @@ pub fn increase_allowance_msgs(
     Ok((msgs, funds))
 }
 
+/// An asset that was received in a smaller amount than required.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetShortfall {
+    pub info: AssetInfo,
+    pub min: Uint128,
+    pub received: Uint128,
+}
+
+impl AssetShortfall {
+    /// Returns how much less than `min` was received.
+    pub fn shortfall(&self) -> Uint128 {
+        self.min - self.received
+    }
+}
+
+impl fmt::Display for AssetShortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: received {}, minimum {} (short by {})",
+            self.info,
+            self.received,
+            self.min,
+            self.shortfall()
+        )
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MinReceivedError {
+    #[error("Max slippage must be between 0 and 1, got {0}")]
+    InvalidMaxSlippage(Decimal),
+
+    #[error("Minimum received assertion failed for: {}", display_shortfalls(.0))]
+    MinNotReceived(Vec<AssetShortfall>),
+}
+
+impl From<MinReceivedError> for StdError {
+    fn from(err: MinReceivedError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+fn display_shortfalls(shortfalls: &[AssetShortfall]) -> String {
+    shortfalls
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Assert that for every asset in `min`, at least that amount of the asset is
+/// in `actual`. Assets in `actual` that are not in `min` are ignored.
+///
+/// ### Errors
+/// Returns [`MinReceivedError::MinNotReceived`] listing every asset that
+/// missed its minimum, together with the amount actually received.
+pub fn assert_min_received(actual: &AssetList, min: &AssetList) -> Result<(), MinReceivedError> {
+    let shortfalls = min
+        .into_iter()
+        .filter_map(|min_asset| {
+            let received = actual
+                .find(&min_asset.info)
+                .map(|a| a.amount)
+                .unwrap_or_default();
+            (received < min_asset.amount).then(|| AssetShortfall {
+                info: min_asset.info.clone(),
+                min: min_asset.amount,
+                received,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if shortfalls.is_empty() {
+        Ok(())
+    } else {
+        Err(MinReceivedError::MinNotReceived(shortfalls))
+    }
+}
+
+/// Assert that every asset in `expected` was received in `actual` with at most
+/// `max_slippage` deviation, i.e. that at least
+/// `expected_amount * (1 - max_slippage)` (rounded down) was received.
+///
+/// ### Errors
+/// Returns [`MinReceivedError::InvalidMaxSlippage`] if `max_slippage` is
+/// greater than one.
+/// Returns [`MinReceivedError::MinNotReceived`] listing every asset that
+/// missed its minimum.
+pub fn assert_max_slippage(
+    actual: &AssetList,
+    expected: &AssetList,
+    max_slippage: Decimal,
+) -> Result<(), MinReceivedError> {
+    let min_ratio = Decimal::one()
+        .checked_sub(max_slippage)
+        .map_err(|_| MinReceivedError::InvalidMaxSlippage(max_slippage))?;
+
+    let min = AssetList::from(
+        expected
+            .into_iter()
+            .map(|asset| Asset::new(asset.info.clone(), asset.amount.mul_floor(min_ratio))),
+    );
+
+    assert_min_received(actual, &min)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,7 +425,7 @@ mod tests {
     use cosmwasm_std::ReplyOn::Never;
     use cosmwasm_std::StdError::GenericErr;
     use cosmwasm_std::WasmMsg::Execute;
-    use cosmwasm_std::{to_binary, Addr, Coin, SubMsg, Uint128};
+    use cosmwasm_std::{to_json_binary, Addr, Coin, SubMsg, Uint128};
     use cw20::{Cw20ExecuteMsg, Expiration};
     use test_case::test_case;
 
@@ -313,11 +485,11 @@ mod tests {
             attr("asset", "apollo:1000"),
         ])];
 
-        let expected_messages = vec![SubMsg {
+        let expected_messages = [SubMsg {
             id: 0,
             msg: Wasm(Execute {
                 contract_addr: String::from("apollo"),
-                msg: to_binary(
+                msg: to_json_binary(
                     &(Cw20ExecuteMsg::TransferFrom {
                         owner: String::from("addr"),
                         recipient: String::from("cosmos2contract"),
@@ -369,7 +541,7 @@ mod tests {
                 "Native token quantity mismatch")]
     fn test_assert_native_token_received(asset: Asset, funds: Vec<Coin>) -> StdResult<()> {
         let info = MessageInfo {
-            funds: funds,
+            funds,
             sender: Addr::unchecked("sender"),
         };
         assert_native_token_received(&info, &asset)
@@ -420,8 +592,74 @@ mod tests {
         let empty_asset_list = to_asset_list(&api, coins, cw20s).unwrap();
         let (coins, cw20s) = separate_natives_and_cw20s(&empty_asset_list);
 
-        assert!(coins.len() == 0);
-        assert!(cw20s.len() == 0);
+        assert!(coins.is_empty());
+        assert!(cw20s.is_empty());
+    }
+
+    #[test]
+    fn test_check_cw20_coins() {
+        let api = MockApi::default();
+        let cw20s = vec![
+            Cw20Coin {
+                address: "osmo1".to_owned(),
+                amount: Uint128::new(100),
+            },
+            Cw20Coin {
+                address: "osmo2".to_owned(),
+                amount: Uint128::new(200),
+            },
+        ];
+
+        let verified = check_cw20_coins(&api, &cw20s).unwrap();
+
+        assert_eq!(
+            verified,
+            vec![
+                Cw20CoinVerified {
+                    address: Addr::unchecked("osmo1"),
+                    amount: Uint128::new(100),
+                },
+                Cw20CoinVerified {
+                    address: Addr::unchecked("osmo2"),
+                    amount: Uint128::new(200),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_cw20_coin_invalid_address() {
+        let api = MockApi::default();
+        let cw20 = Cw20Coin {
+            address: "Osmo1".to_owned(),
+            amount: Uint128::new(100),
+        };
+
+        assert!(check_cw20_coin(&api, &cw20).is_err());
+    }
+
+    #[test]
+    fn test_separate_natives_and_cw20s_verified() {
+        let cw20s = vec![
+            Cw20CoinVerified {
+                address: Addr::unchecked("osmo1"),
+                amount: Uint128::new(100),
+            },
+            Cw20CoinVerified {
+                address: Addr::unchecked("osmo2"),
+                amount: Uint128::new(200),
+            },
+        ];
+        let coins = vec![Coin::new(10, "uosmo"), Coin::new(20, "uatom")];
+
+        let asset_list = to_asset_list_verified(Some(&coins), Some(&cw20s)).unwrap();
+        let (separated_coins, separated_cw20s) = separate_natives_and_cw20s_verified(&asset_list);
+
+        assert_eq!(
+            separated_coins,
+            vec![Coin::new(20, "uatom"), Coin::new(10, "uosmo")]
+        );
+        assert_eq!(separated_cw20s, cw20s);
     }
 
     #[test]
@@ -572,7 +810,7 @@ mod tests {
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "cw20".to_string(),
                 funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
                     spender: spender.to_string(),
                     amount: Uint128::new(200),
                     expires: Some(Expiration::AtHeight(env.block.height + 1)),
@@ -584,4 +822,88 @@ mod tests {
         assert_eq!(funds[0].amount, Uint128::new(100));
         assert_eq!(funds[0].denom, "uatom");
     }
+
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(100, "uatom")].into(),
+        vec![Coin::new(100, "uosmo"), Coin::new(90, "uatom")].into()
+        => Ok(());
+        "All minimums met")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(100, "uatom")].into(),
+        AssetList::new()
+        => Ok(());
+        "Empty minimum")]
+    #[test_case(
+        vec![Coin::new(99, "uosmo"), Coin::new(100, "uatom")].into(),
+        vec![Coin::new(100, "uosmo"), Coin::new(90, "uatom")].into()
+        => Err(MinReceivedError::MinNotReceived(vec![AssetShortfall {
+            info: AssetInfo::native("uosmo"),
+            min: Uint128::new(100),
+            received: Uint128::new(99),
+        }]));
+        "One asset short")]
+    #[test_case(
+        vec![Coin::new(99, "uosmo")].into(),
+        vec![Coin::new(100, "uosmo"), Coin::new(90, "uatom")].into()
+        => Err(MinReceivedError::MinNotReceived(vec![
+            AssetShortfall {
+                info: AssetInfo::native("uosmo"),
+                min: Uint128::new(100),
+                received: Uint128::new(99),
+            },
+            AssetShortfall {
+                info: AssetInfo::native("uatom"),
+                min: Uint128::new(90),
+                received: Uint128::zero(),
+            },
+        ]));
+        "One asset short, one missing")]
+    fn test_assert_min_received(actual: AssetList, min: AssetList) -> Result<(), MinReceivedError> {
+        assert_min_received(&actual, &min)
+    }
+
+    #[test_case(
+        vec![Coin::new(95, "uosmo")].into(),
+        vec![Coin::new(100, "uosmo")].into(),
+        Decimal::percent(5)
+        => Ok(());
+        "Slippage exactly at max")]
+    #[test_case(
+        vec![Coin::new(94, "uosmo")].into(),
+        vec![Coin::new(100, "uosmo")].into(),
+        Decimal::percent(5)
+        => Err(MinReceivedError::MinNotReceived(vec![AssetShortfall {
+            info: AssetInfo::native("uosmo"),
+            min: Uint128::new(95),
+            received: Uint128::new(94),
+        }]));
+        "Slippage above max")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo")].into(),
+        vec![Coin::new(100, "uosmo")].into(),
+        Decimal::percent(101)
+        => Err(MinReceivedError::InvalidMaxSlippage(Decimal::percent(101)));
+        "Invalid max slippage")]
+    fn test_assert_max_slippage(
+        actual: AssetList,
+        expected: AssetList,
+        max_slippage: Decimal,
+    ) -> Result<(), MinReceivedError> {
+        assert_max_slippage(&actual, &expected, max_slippage)
+    }
+
+    #[test]
+    fn test_min_received_error_message() {
+        let err: StdError = assert_min_received(
+            &vec![Coin::new(99, "uosmo")].into(),
+            &vec![Coin::new(100, "uosmo")].into(),
+        )
+        .unwrap_err()
+        .into();
+
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Minimum received assertion failed for: uosmo: received 99, minimum 100 (short by 1)"
+        );
+    }
 }
@@ -0,0 +1,439 @@
+use apollo_cw_asset::{Asset, AssetInfo, AssetList};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    attr, Addr, Env, Event, MessageInfo, Order, Response, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::{Bound, Map};
+
+use super::{receive_assets, AssetKey};
+use crate::pagination::page_limit;
+
+/// A single escrowed asset of a user.
+#[cw_serde]
+pub struct EscrowEntry {
+    pub user: Addr,
+    pub asset: Asset,
+}
+
+/// A ledger of assets held in escrow on behalf of users, stored per
+/// (user, `AssetInfo`).
+pub struct EscrowLedger<'a>(Map<'a, (Addr, AssetKey), Uint128>);
+
+impl<'a> EscrowLedger<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self(Map::new(namespace))
+    }
+
+    /// Receives the assets with [`receive_assets`] and, if that succeeds,
+    /// records them as escrowed for `info.sender`.
+    ///
+    /// ### Returns
+    /// Returns the response of [`receive_assets`].
+    pub fn deposit(
+        &self,
+        storage: &mut dyn Storage,
+        info: &MessageInfo,
+        env: &Env,
+        assets: &AssetList,
+    ) -> StdResult<Response> {
+        let res = receive_assets(info, env, assets)?;
+        for asset in assets {
+            self.0.update(
+                storage,
+                (info.sender.clone(), (&asset.info).into()),
+                |balance| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_add(asset.amount)?)
+                },
+            )?;
+        }
+        Ok(res)
+    }
+
+    /// Removes the assets from the escrow of `user` and returns a `Response`
+    /// with messages transferring them to `recipient`. Any amount up to the
+    /// escrowed balance can be withdrawn. Assets with zero amount are skipped,
+    /// since the chain rejects transfers of zero tokens.
+    ///
+    /// ### Errors
+    /// Returns an error if `user` has less than the requested amount of any of
+    /// the assets in escrow.
+    pub fn withdraw(
+        &self,
+        storage: &mut dyn Storage,
+        user: &Addr,
+        assets: &AssetList,
+        recipient: &Addr,
+    ) -> StdResult<Response> {
+        let assets: AssetList = assets
+            .into_iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .cloned()
+            .collect::<Vec<_>>()
+            .into();
+
+        for asset in &assets {
+            let balance = self.balance(storage, user, &asset.info)?;
+            let key = (user.clone(), AssetKey::from(&asset.info));
+            match balance.checked_sub(asset.amount)? {
+                remaining if remaining.is_zero() => self.0.remove(storage, key),
+                remaining => self.0.save(storage, key, &remaining)?,
+            }
+        }
+
+        let event = Event::new("apollo/utils/escrow").add_attributes(vec![
+            attr("action", "withdraw"),
+            attr("user", user),
+            attr("recipient", recipient),
+            attr("assets", assets.to_string()),
+        ]);
+        Ok(Response::new()
+            .add_messages(assets.transfer_msgs(recipient)?)
+            .add_event(event))
+    }
+
+    /// Returns the escrowed amount of an asset for `user`.
+    pub fn balance(
+        &self,
+        storage: &dyn Storage,
+        user: &Addr,
+        info: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        Ok(self
+            .0
+            .may_load(storage, (user.clone(), info.into()))?
+            .unwrap_or_default())
+    }
+
+    /// Returns the escrowed assets of `user`, paginated by `AssetInfo`.
+    pub fn query_user_deposits(
+        &self,
+        storage: &dyn Storage,
+        user: &Addr,
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    ) -> StdResult<AssetList> {
+        let limit = page_limit(limit);
+        let start = start_after.map(|info| Bound::exclusive(AssetKey::from(info)));
+
+        let assets = self
+            .0
+            .prefix(user.clone())
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(info, amount)| Asset::new(info, amount)))
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(assets.into())
+    }
+
+    /// Returns the escrowed assets of all users, paginated by
+    /// (user, `AssetInfo`).
+    pub fn query_all_deposits(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<(Addr, AssetInfo)>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<EscrowEntry>> {
+        let limit = page_limit(limit);
+        let start = start_after.map(|(user, info)| Bound::exclusive((user, AssetKey::from(info))));
+
+        self.0
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|((user, info), amount)| EscrowEntry {
+                    user,
+                    asset: Asset::new(info, amount),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockStorage};
+    use cosmwasm_std::{BankMsg, Coin, OverflowError, OverflowOperation, StdError};
+
+    const ESCROW: EscrowLedger = EscrowLedger::new("escrow");
+
+    fn setup(storage: &mut MockStorage) {
+        let env = mock_env();
+        let alice_assets = AssetList::from(vec![
+            Asset::native("uosmo", 100u128),
+            Asset::new(AssetInfo::cw20(Addr::unchecked("apollo")), 50u128),
+        ]);
+        ESCROW
+            .deposit(
+                storage,
+                &mock_info("alice", &[Coin::new(100, "uosmo")]),
+                &env,
+                &alice_assets,
+            )
+            .unwrap();
+        ESCROW
+            .deposit(
+                storage,
+                &mock_info("bob", &[Coin::new(10, "uatom")]),
+                &env,
+                &vec![Coin::new(10, "uatom")].into(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deposit_records_balances() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+
+        let alice = Addr::unchecked("alice");
+        assert_eq!(
+            ESCROW
+                .balance(&storage, &alice, &AssetInfo::native("uosmo"))
+                .unwrap(),
+            Uint128::new(100)
+        );
+        assert_eq!(
+            ESCROW
+                .balance(
+                    &storage,
+                    &alice,
+                    &AssetInfo::cw20(Addr::unchecked("apollo"))
+                )
+                .unwrap(),
+            Uint128::new(50)
+        );
+        assert_eq!(
+            ESCROW
+                .balance(&storage, &alice, &AssetInfo::native("uatom"))
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn test_deposit_not_recorded_if_funds_not_sent() {
+        let mut storage = MockStorage::new();
+
+        let err = ESCROW
+            .deposit(
+                &mut storage,
+                &mock_info("alice", &[]),
+                &mock_env(),
+                &vec![Coin::new(100, "uosmo")].into(),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            StdError::generic_err("Assert native token received failed for asset: uosmo:100")
+        );
+        assert_eq!(
+            ESCROW
+                .query_all_deposits(&storage, None, None)
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_partial_withdraw() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+        let alice = Addr::unchecked("alice");
+        let recipient = Addr::unchecked("recipient");
+
+        let res = ESCROW
+            .withdraw(
+                &mut storage,
+                &alice,
+                &vec![Coin::new(40, "uosmo")].into(),
+                &recipient,
+            )
+            .unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin::new(40, "uosmo")],
+            }
+            .into()
+        );
+        assert_eq!(
+            ESCROW
+                .balance(&storage, &alice, &AssetInfo::native("uosmo"))
+                .unwrap(),
+            Uint128::new(60)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_skips_zero_amounts() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+        let alice = Addr::unchecked("alice");
+        let recipient = Addr::unchecked("recipient");
+
+        let res = ESCROW
+            .withdraw(
+                &mut storage,
+                &alice,
+                &vec![Coin::new(0, "uatom"), Coin::new(40, "uosmo")].into(),
+                &recipient,
+            )
+            .unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin::new(40, "uosmo")],
+            }
+            .into()
+        );
+
+        let res = ESCROW
+            .withdraw(
+                &mut storage,
+                &alice,
+                &vec![Asset::new(
+                    AssetInfo::cw20(Addr::unchecked("apollo")),
+                    0u128,
+                )]
+                .into(),
+                &recipient,
+            )
+            .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            ESCROW
+                .balance(
+                    &storage,
+                    &alice,
+                    &AssetInfo::cw20(Addr::unchecked("apollo"))
+                )
+                .unwrap(),
+            Uint128::new(50)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_more_than_escrowed() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+
+        let err = ESCROW
+            .withdraw(
+                &mut storage,
+                &Addr::unchecked("bob"),
+                &vec![Coin::new(11, "uatom")].into(),
+                &Addr::unchecked("bob"),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            StdError::overflow(OverflowError::new(OverflowOperation::Sub, 10u128, 11u128))
+        );
+    }
+
+    #[test]
+    fn test_full_withdraw_removes_entry() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+        let bob = Addr::unchecked("bob");
+
+        ESCROW
+            .withdraw(
+                &mut storage,
+                &bob,
+                &vec![Coin::new(10, "uatom")].into(),
+                &bob,
+            )
+            .unwrap();
+
+        assert_eq!(
+            ESCROW
+                .query_user_deposits(&storage, &bob, None, None)
+                .unwrap(),
+            AssetList::new()
+        );
+    }
+
+    #[test]
+    fn test_query_user_deposits_pagination() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+        let alice = Addr::unchecked("alice");
+
+        let page1 = ESCROW
+            .query_user_deposits(&storage, &alice, None, Some(1))
+            .unwrap();
+        assert_eq!(
+            page1,
+            vec![Asset::new(
+                AssetInfo::cw20(Addr::unchecked("apollo")),
+                50u128
+            )]
+            .into()
+        );
+
+        let page2 = ESCROW
+            .query_user_deposits(
+                &storage,
+                &alice,
+                Some(AssetInfo::cw20(Addr::unchecked("apollo"))),
+                Some(1),
+            )
+            .unwrap();
+        assert_eq!(page2, vec![Asset::native("uosmo", 100u128)].into());
+
+        let page3 = ESCROW
+            .query_user_deposits(&storage, &alice, Some(AssetInfo::native("uosmo")), None)
+            .unwrap();
+        assert_eq!(page3, AssetList::new());
+    }
+
+    #[test]
+    fn test_query_all_deposits_pagination() {
+        let mut storage = MockStorage::new();
+        setup(&mut storage);
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        // Keys are length-prefixed, so shorter addresses come first
+        let page1 = ESCROW.query_all_deposits(&storage, None, Some(2)).unwrap();
+        assert_eq!(
+            page1,
+            vec![
+                EscrowEntry {
+                    user: bob,
+                    asset: Asset::native("uatom", 10u128),
+                },
+                EscrowEntry {
+                    user: alice.clone(),
+                    asset: Asset::new(AssetInfo::cw20(Addr::unchecked("apollo")), 50u128),
+                },
+            ]
+        );
+
+        let page2 = ESCROW
+            .query_all_deposits(
+                &storage,
+                Some((alice.clone(), AssetInfo::cw20(Addr::unchecked("apollo")))),
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            page2,
+            vec![EscrowEntry {
+                user: alice,
+                asset: Asset::native("uosmo", 100u128),
+            }]
+        );
+    }
+}
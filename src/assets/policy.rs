@@ -0,0 +1,234 @@
+use apollo_cw_asset::{Asset, AssetInfo, AssetInfoBase, AssetList};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, Coin, Env, MessageInfo, Response, StdError, StdResult};
+
+use super::{assert_native_tokens_received, receive_assets};
+
+/// A policy restricting which assets a contract accepts.
+#[cw_serde]
+pub enum AssetPolicyBase<T> {
+    /// Only the listed assets are accepted.
+    Allowlist(Vec<AssetInfoBase<T>>),
+    /// All assets except the listed ones are accepted.
+    Denylist(Vec<AssetInfoBase<T>>),
+    /// Only native tokens are accepted.
+    NativesOnly,
+}
+
+pub type AssetPolicyUnchecked = AssetPolicyBase<String>;
+pub type AssetPolicy = AssetPolicyBase<Addr>;
+
+impl AssetPolicyUnchecked {
+    /// Validate the CW20 addresses in the policy.
+    pub fn check(&self, api: &dyn Api) -> StdResult<AssetPolicy> {
+        let check_all = |infos: &Vec<AssetInfoBase<String>>| {
+            infos
+                .iter()
+                .map(|info| info.check(api))
+                .collect::<StdResult<Vec<_>>>()
+        };
+        Ok(match self {
+            AssetPolicyBase::Allowlist(infos) => AssetPolicyBase::Allowlist(check_all(infos)?),
+            AssetPolicyBase::Denylist(infos) => AssetPolicyBase::Denylist(check_all(infos)?),
+            AssetPolicyBase::NativesOnly => AssetPolicyBase::NativesOnly,
+        })
+    }
+}
+
+impl From<AssetPolicy> for AssetPolicyUnchecked {
+    fn from(policy: AssetPolicy) -> Self {
+        let uncheck_all = |infos: Vec<AssetInfo>| infos.into_iter().map(Into::into).collect();
+        match policy {
+            AssetPolicyBase::Allowlist(infos) => AssetPolicyBase::Allowlist(uncheck_all(infos)),
+            AssetPolicyBase::Denylist(infos) => AssetPolicyBase::Denylist(uncheck_all(infos)),
+            AssetPolicyBase::NativesOnly => AssetPolicyBase::NativesOnly,
+        }
+    }
+}
+
+impl AssetPolicy {
+    /// Returns true if the policy accepts the given `AssetInfo`.
+    pub fn is_allowed(&self, info: &AssetInfo) -> bool {
+        match self {
+            AssetPolicyBase::Allowlist(infos) => infos.contains(info),
+            AssetPolicyBase::Denylist(infos) => !infos.contains(info),
+            AssetPolicyBase::NativesOnly => info.is_native(),
+        }
+    }
+
+    /// Assert that the policy accepts the given `AssetInfo`.
+    pub fn validate_asset_info(&self, info: &AssetInfo) -> StdResult<()> {
+        if self.is_allowed(info) {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(format!(
+                "Asset {} is not allowed by asset policy",
+                info
+            )))
+        }
+    }
+
+    /// Assert that the policy accepts the given `Asset`.
+    pub fn validate_asset(&self, asset: &Asset) -> StdResult<()> {
+        self.validate_asset_info(&asset.info)
+    }
+
+    /// Assert that the policy accepts all assets in the given `AssetList`.
+    pub fn validate_asset_list(&self, assets: &AssetList) -> StdResult<()> {
+        assets
+            .into_iter()
+            .try_for_each(|asset| self.validate_asset(asset))
+    }
+
+    /// Assert that the policy accepts all of the given native coins.
+    pub fn validate_coins(&self, coins: &[Coin]) -> StdResult<()> {
+        coins
+            .iter()
+            .try_for_each(|coin| self.validate_asset_info(&AssetInfo::native(&coin.denom)))
+    }
+}
+
+/// Same as [`receive_assets`], but first asserts that all assets are accepted
+/// by `policy`, if one is given.
+pub fn receive_assets_with_policy(
+    info: &MessageInfo,
+    env: &Env,
+    assets: &AssetList,
+    policy: Option<&AssetPolicy>,
+) -> StdResult<Response> {
+    if let Some(policy) = policy {
+        policy.validate_asset_list(assets)?;
+    }
+    receive_assets(info, env, assets)
+}
+
+/// Same as [`assert_native_tokens_received`], but first asserts that all
+/// assets in the `AssetList` and all native tokens in `info.funds` are accepted
+/// by `policy`, if one is given.
+pub fn assert_native_tokens_received_with_policy(
+    info: &MessageInfo,
+    assets: &AssetList,
+    policy: Option<&AssetPolicy>,
+) -> StdResult<Vec<Coin>> {
+    if let Some(policy) = policy {
+        policy.validate_asset_list(assets)?;
+        policy.validate_coins(&info.funds)?;
+    }
+    assert_native_tokens_received(info, assets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi};
+    use test_case::test_case;
+
+    #[test_case(
+        AssetPolicy::Allowlist(vec![AssetInfo::native("uosmo"), AssetInfo::cw20(Addr::unchecked("apollo"))]),
+        AssetInfo::native("uosmo") => true;
+        "Allowlist, native listed")]
+    #[test_case(
+        AssetPolicy::Allowlist(vec![AssetInfo::native("uosmo"), AssetInfo::cw20(Addr::unchecked("apollo"))]),
+        AssetInfo::cw20(Addr::unchecked("apollo")) => true;
+        "Allowlist, cw20 listed")]
+    #[test_case(
+        AssetPolicy::Allowlist(vec![AssetInfo::native("uosmo")]),
+        AssetInfo::native("uatom") => false;
+        "Allowlist, not listed")]
+    #[test_case(
+        AssetPolicy::Allowlist(vec![AssetInfo::native("apollo")]),
+        AssetInfo::cw20(Addr::unchecked("apollo")) => false;
+        "Allowlist, native with same name as cw20")]
+    #[test_case(
+        AssetPolicy::Denylist(vec![AssetInfo::native("uosmo")]),
+        AssetInfo::native("uosmo") => false;
+        "Denylist, listed")]
+    #[test_case(
+        AssetPolicy::Denylist(vec![AssetInfo::native("uosmo")]),
+        AssetInfo::cw20(Addr::unchecked("apollo")) => true;
+        "Denylist, not listed")]
+    #[test_case(
+        AssetPolicy::NativesOnly,
+        AssetInfo::native("uosmo") => true;
+        "Natives only, native")]
+    #[test_case(
+        AssetPolicy::NativesOnly,
+        AssetInfo::cw20(Addr::unchecked("apollo")) => false;
+        "Natives only, cw20")]
+    fn test_is_allowed(policy: AssetPolicy, info: AssetInfo) -> bool {
+        policy.is_allowed(&info)
+    }
+
+    #[test]
+    fn test_validate_asset_list() {
+        let policy = AssetPolicy::Allowlist(vec![AssetInfo::native("uosmo")]);
+
+        assert!(policy
+            .validate_asset_list(&vec![Coin::new(100, "uosmo")].into())
+            .is_ok());
+        assert_eq!(
+            policy
+                .validate_asset_list(&vec![Coin::new(100, "uosmo"), Coin::new(100, "uatom")].into())
+                .unwrap_err(),
+            StdError::generic_err("Asset uatom is not allowed by asset policy")
+        );
+    }
+
+    #[test]
+    fn test_check_unchecked_policy() {
+        let api = MockApi::default();
+        let unchecked = AssetPolicyUnchecked::Denylist(vec![
+            AssetInfoBase::Native("uosmo".to_string()),
+            AssetInfoBase::Cw20("apollo".to_string()),
+        ]);
+
+        let checked = unchecked.check(&api).unwrap();
+
+        assert_eq!(
+            checked,
+            AssetPolicy::Denylist(vec![
+                AssetInfo::native("uosmo"),
+                AssetInfo::cw20(Addr::unchecked("apollo"))
+            ])
+        );
+        assert_eq!(AssetPolicyUnchecked::from(checked), unchecked);
+    }
+
+    #[test]
+    fn test_receive_assets_with_policy() {
+        let info = mock_info("addr", &[]);
+        let env = mock_env();
+        let assets: AssetList = vec![Asset::new(
+            AssetInfo::cw20(Addr::unchecked("apollo")),
+            100u128,
+        )]
+        .into();
+
+        assert!(receive_assets_with_policy(&info, &env, &assets, None).is_ok());
+        assert!(receive_assets_with_policy(
+            &info,
+            &env,
+            &assets,
+            Some(&AssetPolicy::Denylist(vec![]))
+        )
+        .is_ok());
+        assert_eq!(
+            receive_assets_with_policy(&info, &env, &assets, Some(&AssetPolicy::NativesOnly))
+                .unwrap_err(),
+            StdError::generic_err("Asset apollo is not allowed by asset policy")
+        );
+    }
+
+    #[test]
+    fn test_assert_native_tokens_received_with_policy_checks_funds() {
+        let info = mock_info("addr", &[Coin::new(100, "uosmo"), Coin::new(100, "uatom")]);
+        let assets: AssetList = vec![Coin::new(100, "uosmo")].into();
+        let policy = AssetPolicy::Denylist(vec![AssetInfo::native("uatom")]);
+
+        assert!(assert_native_tokens_received_with_policy(&info, &assets, None).is_ok());
+        assert_eq!(
+            assert_native_tokens_received_with_policy(&info, &assets, Some(&policy)).unwrap_err(),
+            StdError::generic_err("Asset uatom is not allowed by asset policy")
+        );
+    }
+}
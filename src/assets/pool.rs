@@ -0,0 +1,158 @@
+use apollo_cw_asset::{Asset, AssetList};
+use cosmwasm_std::{Decimal, StdError, StdResult};
+
+/// Split a deposit into a pool into the largest part that matches the ratio of
+/// the pool reserves, and the excess that should be refunded.
+///
+/// ### Returns
+/// Returns a `StdResult<(AssetList, AssetList)>` containing the balanced
+/// deposit and the excess.
+///
+/// ### Errors
+/// Returns an error if the deposit contains an asset that is not in the pool.
+/// Returns an error if, for any asset, the excess is more than `tolerance` of
+/// the deposited amount.
+pub fn balanced_deposit(
+    deposit: &AssetList,
+    reserves: &AssetList,
+    tolerance: Decimal,
+) -> StdResult<(AssetList, AssetList)> {
+    let amounts = reserves
+        .into_iter()
+        .map(|reserve| {
+            let deposited = deposit
+                .find(&reserve.info)
+                .map(|a| a.amount)
+                .unwrap_or_default();
+            (reserve, deposited)
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(asset) = deposit
+        .into_iter()
+        .find(|asset| reserves.find(&asset.info).is_none())
+    {
+        return Err(StdError::generic_err(format!(
+            "Asset {} is not in the pool",
+            asset.info
+        )));
+    }
+
+    // The asset with the smallest deposit to reserve ratio limits how much of
+    // the other assets can be deposited.
+    let limiting = amounts
+        .iter()
+        .filter(|(reserve, _)| !reserve.amount.is_zero())
+        .min_by(|(reserve_a, deposit_a), (reserve_b, deposit_b)| {
+            deposit_a
+                .full_mul(reserve_b.amount)
+                .cmp(&deposit_b.full_mul(reserve_a.amount))
+        });
+    let (limiting_reserve, limiting_deposit) = match limiting {
+        Some((reserve, deposited)) => (reserve.amount, *deposited),
+        None => return Ok((deposit.clone(), AssetList::new())),
+    };
+
+    let mut balanced = AssetList::new();
+    let mut excess = AssetList::new();
+    for (reserve, deposited) in amounts {
+        let balanced_amount = reserve
+            .amount
+            .multiply_ratio(limiting_deposit, limiting_reserve);
+        let excess_amount = deposited - balanced_amount;
+
+        if !excess_amount.is_zero() {
+            let deviation = Decimal::from_ratio(excess_amount, deposited);
+            if deviation > tolerance {
+                return Err(StdError::generic_err(format!(
+                    "Deposit of {} deviates from pool ratio by {}, max allowed is {}",
+                    reserve.info, deviation, tolerance
+                )));
+            }
+        }
+
+        balanced.add(&Asset::new(reserve.info.clone(), balanced_amount))?;
+        excess.add(&Asset::new(reserve.info.clone(), excess_amount))?;
+    }
+
+    Ok((balanced, excess))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use apollo_cw_asset::AssetInfo;
+    use cosmwasm_std::{Addr, Coin};
+    use test_case::test_case;
+
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(200, "uatom")].into(),
+        vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uatom")].into(),
+        Decimal::zero()
+        => Ok((vec![Coin::new(100, "uosmo"), Coin::new(200, "uatom")].into(), AssetList::new()));
+        "Exactly balanced")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(210, "uatom")].into(),
+        vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uatom")].into(),
+        Decimal::percent(5)
+        => Ok((
+            vec![Coin::new(100, "uosmo"), Coin::new(200, "uatom")].into(),
+            vec![Coin::new(10, "uatom")].into()
+        ));
+        "Excess within tolerance")]
+    #[test_case(
+        vec![Coin::new(90, "uosmo"), Coin::new(200, "uatom")].into(),
+        vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uatom")].into(),
+        Decimal::percent(5)
+        => Err(StdError::generic_err("Deposit of uatom deviates from pool ratio by 0.1, max allowed is 0.05"));
+        "Excess above tolerance")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo")].into(),
+        vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uatom")].into(),
+        Decimal::one()
+        => Ok((AssetList::new(), vec![Coin::new(100, "uosmo")].into()));
+        "Missing pool asset in deposit")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(100, "uion")].into(),
+        vec![Coin::new(1000, "uosmo")].into(),
+        Decimal::one()
+        => Err(StdError::generic_err("Asset uion is not in the pool"));
+        "Asset not in pool")]
+    #[test_case(
+        vec![Coin::new(100, "uosmo"), Coin::new(7, "uatom")].into(),
+        AssetList::new(),
+        Decimal::zero()
+        => Err(StdError::generic_err("Asset uosmo is not in the pool"));
+        "Empty pool")]
+    fn test_balanced_deposit(
+        deposit: AssetList,
+        reserves: AssetList,
+        tolerance: Decimal,
+    ) -> StdResult<(AssetList, AssetList)> {
+        balanced_deposit(&deposit, &reserves, tolerance)
+    }
+
+    #[test]
+    fn test_balanced_deposit_rounds_down() {
+        let cw20 = AssetInfo::cw20(Addr::unchecked("apollo"));
+        let deposit = AssetList::from(vec![
+            Asset::native("uosmo", 10u128),
+            Asset::new(cw20.clone(), 10u128),
+        ]);
+        let reserves = AssetList::from(vec![
+            Asset::native("uosmo", 3u128),
+            Asset::new(cw20.clone(), 2u128),
+        ]);
+
+        let (balanced, excess) = balanced_deposit(&deposit, &reserves, Decimal::one()).unwrap();
+
+        assert_eq!(
+            balanced,
+            AssetList::from(vec![
+                Asset::native("uosmo", 10u128),
+                Asset::new(cw20.clone(), 6u128),
+            ])
+        );
+        assert_eq!(excess, AssetList::from(vec![Asset::new(cw20, 4u128)]));
+    }
+}
@@ -0,0 +1,433 @@
+use apollo_cw_asset::{Asset, AssetInfo, AssetList};
+use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Key, KeyDeserialize, Map, Prefixer, PrimaryKey};
+
+const NATIVE_TAG: u8 = b'n';
+const CW20_TAG: u8 = b'c';
+
+/// The kind of an asset. Can be used to iterate over only the native or only
+/// the CW20 entries of a `Map` keyed by [`AssetKey`], using the bounds
+/// returned by [`AssetKind::bounds`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssetKind {
+    Native,
+    Cw20,
+}
+
+impl AssetKind {
+    fn tag(&self) -> u8 {
+        match self {
+            AssetKind::Native => NATIVE_TAG,
+            AssetKind::Cw20 => CW20_TAG,
+        }
+    }
+
+    /// Returns the `(min, max)` bounds to pass to `range` to iterate over only
+    /// the assets of this kind.
+    pub fn bounds<'a>(&self) -> (Option<Bound<'a, AssetKey>>, Option<Bound<'a, AssetKey>>) {
+        (
+            Some(Bound::InclusiveRaw(vec![self.tag()])),
+            Some(Bound::ExclusiveRaw(vec![self.tag() + 1])),
+        )
+    }
+}
+
+/// A wrapper around `AssetInfo` that can be used as a key in `cw-storage-plus`
+/// maps.
+///
+/// The key is a single part consisting of a one byte tag for the kind of the
+/// asset (`n` for native, `c` for CW20) followed by the denom or contract
+/// address. Native tokens and CW20s therefore never collide, and the key can be
+/// used at any position of a composite key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetKey {
+    info: AssetInfo,
+    bytes: Vec<u8>,
+}
+
+impl AssetKey {
+    pub fn new(info: AssetInfo) -> Self {
+        let kind = asset_kind(&info);
+        let value = match &info {
+            AssetInfo::Native(denom) => denom.as_bytes(),
+            AssetInfo::Cw20(addr) => addr.as_bytes(),
+        };
+        let mut bytes = Vec::with_capacity(value.len() + 1);
+        bytes.push(kind.tag());
+        bytes.extend_from_slice(value);
+        Self { info, bytes }
+    }
+
+    pub fn info(&self) -> &AssetInfo {
+        &self.info
+    }
+
+    pub fn kind(&self) -> AssetKind {
+        asset_kind(&self.info)
+    }
+}
+
+fn asset_kind(info: &AssetInfo) -> AssetKind {
+    match info {
+        AssetInfo::Native(_) => AssetKind::Native,
+        AssetInfo::Cw20(_) => AssetKind::Cw20,
+    }
+}
+
+impl From<AssetInfo> for AssetKey {
+    fn from(info: AssetInfo) -> Self {
+        Self::new(info)
+    }
+}
+
+impl From<&AssetInfo> for AssetKey {
+    fn from(info: &AssetInfo) -> Self {
+        Self::new(info.clone())
+    }
+}
+
+impl From<AssetKey> for AssetInfo {
+    fn from(key: AssetKey) -> Self {
+        key.info
+    }
+}
+
+impl<'a> PrimaryKey<'a> for AssetKey {
+    type Prefix = ();
+    type SubPrefix = ();
+    type Suffix = Self;
+    type SuperSuffix = Self;
+
+    fn key(&self) -> Vec<Key<'_>> {
+        vec![Key::Ref(&self.bytes)]
+    }
+}
+
+impl<'a> Prefixer<'a> for AssetKey {
+    fn prefix(&self) -> Vec<Key<'_>> {
+        self.key()
+    }
+}
+
+impl KeyDeserialize for AssetKey {
+    type Output = AssetInfo;
+
+    fn from_vec(mut value: Vec<u8>) -> StdResult<Self::Output> {
+        if value.is_empty() {
+            return Err(StdError::generic_err("Invalid AssetKey: missing kind"));
+        }
+        let inner = String::from_vec(value.split_off(1))?;
+
+        match value[0] {
+            NATIVE_TAG => Ok(AssetInfo::Native(inner)),
+            CW20_TAG => Ok(AssetInfo::Cw20(Addr::unchecked(inner))),
+            _ => Err(StdError::generic_err("Invalid AssetKey: unknown kind")),
+        }
+    }
+}
+
+/// A `Map`-backed store of asset amounts, e.g. for keeping track of balances.
+/// Entries are removed when their amount reaches zero.
+pub struct AssetListStore<'a>(Map<'a, AssetKey, Uint128>);
+
+impl<'a> AssetListStore<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self(Map::new(namespace))
+    }
+
+    /// Returns the stored amount of the asset, or zero if there is none.
+    pub fn load(&self, storage: &dyn Storage, info: &AssetInfo) -> StdResult<Uint128> {
+        Ok(self.0.may_load(storage, info.into())?.unwrap_or_default())
+    }
+
+    /// Adds the asset to the store and returns the new amount.
+    pub fn add(&self, storage: &mut dyn Storage, asset: &Asset) -> StdResult<Uint128> {
+        let amount = self.load(storage, &asset.info)?.checked_add(asset.amount)?;
+        self.save(storage, &asset.info, amount)?;
+        Ok(amount)
+    }
+
+    /// Adds all assets in the `AssetList` to the store.
+    pub fn add_many(&self, storage: &mut dyn Storage, assets: &AssetList) -> StdResult<()> {
+        assets
+            .into_iter()
+            .try_for_each(|asset| self.add(storage, asset).map(|_| ()))
+    }
+
+    /// Subtracts the asset from the store and returns the new amount.
+    ///
+    /// ### Errors
+    /// Returns an error if the stored amount is less than the asset amount.
+    pub fn subtract(&self, storage: &mut dyn Storage, asset: &Asset) -> StdResult<Uint128> {
+        let amount = self.load(storage, &asset.info)?.checked_sub(asset.amount)?;
+        self.save(storage, &asset.info, amount)?;
+        Ok(amount)
+    }
+
+    /// Subtracts all assets in the `AssetList` from the store.
+    pub fn subtract_many(&self, storage: &mut dyn Storage, assets: &AssetList) -> StdResult<()> {
+        assets
+            .into_iter()
+            .try_for_each(|asset| self.subtract(storage, asset).map(|_| ()))
+    }
+
+    /// Loads all stored assets into an `AssetList`.
+    pub fn load_all(&self, storage: &dyn Storage) -> StdResult<AssetList> {
+        let assets = self
+            .0
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(info, amount)| Asset::new(info, amount)))
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(assets.into())
+    }
+
+    /// Returns the underlying `Map`.
+    pub fn map(&self) -> &Map<'a, AssetKey, Uint128> {
+        &self.0
+    }
+
+    fn save(&self, storage: &mut dyn Storage, info: &AssetInfo, amount: Uint128) -> StdResult<()> {
+        if amount.is_zero() {
+            self.0.remove(storage, info.into());
+            Ok(())
+        } else {
+            self.0.save(storage, info.into(), &amount)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{Coin, OverflowError, OverflowOperation};
+
+    const BALANCES: AssetListStore = AssetListStore::new("balances");
+
+    #[test]
+    fn test_native_and_cw20_keys_do_not_collide() {
+        let mut storage = MockStorage::new();
+        let map: Map<AssetKey, Uint128> = Map::new("map");
+
+        map.save(
+            &mut storage,
+            AssetInfo::native("apollo").into(),
+            &Uint128::new(1),
+        )
+        .unwrap();
+        map.save(
+            &mut storage,
+            AssetInfo::cw20(Addr::unchecked("apollo")).into(),
+            &Uint128::new(2),
+        )
+        .unwrap();
+
+        assert_eq!(
+            map.load(&storage, AssetInfo::native("apollo").into())
+                .unwrap(),
+            Uint128::new(1)
+        );
+        assert_eq!(
+            map.load(&storage, AssetInfo::cw20(Addr::unchecked("apollo")).into())
+                .unwrap(),
+            Uint128::new(2)
+        );
+    }
+
+    #[test]
+    fn test_range_deserializes_asset_info() {
+        let mut storage = MockStorage::new();
+        let map: Map<AssetKey, Uint128> = Map::new("map");
+
+        map.save(
+            &mut storage,
+            AssetInfo::native("uosmo").into(),
+            &Uint128::new(1),
+        )
+        .unwrap();
+        map.save(
+            &mut storage,
+            AssetInfo::cw20(Addr::unchecked("apollo")).into(),
+            &Uint128::new(2),
+        )
+        .unwrap();
+
+        let all = map
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            all,
+            vec![
+                (AssetInfo::cw20(Addr::unchecked("apollo")), Uint128::new(2)),
+                (AssetInfo::native("uosmo"), Uint128::new(1)),
+            ]
+        );
+
+        let (min, max) = AssetKind::Native.bounds();
+        let natives = map
+            .range(&storage, min, max, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(natives, vec![(AssetInfo::native("uosmo"), Uint128::new(1))]);
+
+        let (min, max) = AssetKind::Cw20.bounds();
+        let cw20s = map
+            .range(&storage, min, max, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            cw20s,
+            vec![(AssetInfo::cw20(Addr::unchecked("apollo")), Uint128::new(2))]
+        );
+    }
+
+    #[test]
+    fn test_asset_key_in_composite_key() {
+        let mut storage = MockStorage::new();
+        let map: Map<(&Addr, AssetKey), Uint128> = Map::new("map");
+        let user = Addr::unchecked("user");
+
+        map.save(
+            &mut storage,
+            (&user, AssetInfo::native("uosmo").into()),
+            &Uint128::new(1),
+        )
+        .unwrap();
+        map.save(
+            &mut storage,
+            (&user, AssetInfo::cw20(Addr::unchecked("apollo")).into()),
+            &Uint128::new(2),
+        )
+        .unwrap();
+
+        let user_assets = map
+            .prefix(&user)
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            user_assets,
+            vec![
+                (AssetInfo::cw20(Addr::unchecked("apollo")), Uint128::new(2)),
+                (AssetInfo::native("uosmo"), Uint128::new(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_asset_key_first_in_composite_key() {
+        let mut storage = MockStorage::new();
+        let map: Map<(AssetKey, &Addr), Uint128> = Map::new("map");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        map.save(
+            &mut storage,
+            (AssetInfo::native("uosmo").into(), &alice),
+            &Uint128::new(1),
+        )
+        .unwrap();
+        map.save(
+            &mut storage,
+            (AssetInfo::native("uosmo").into(), &bob),
+            &Uint128::new(2),
+        )
+        .unwrap();
+        map.save(
+            &mut storage,
+            (AssetInfo::cw20(Addr::unchecked("apollo")).into(), &alice),
+            &Uint128::new(3),
+        )
+        .unwrap();
+
+        // Parts that are not last are length-prefixed, so the shorter key sorts
+        // first
+        let all = map
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            all,
+            vec![
+                ((AssetInfo::native("uosmo"), alice.clone()), Uint128::new(1)),
+                ((AssetInfo::native("uosmo"), bob.clone()), Uint128::new(2)),
+                (
+                    (AssetInfo::cw20(Addr::unchecked("apollo")), alice.clone()),
+                    Uint128::new(3)
+                ),
+            ]
+        );
+
+        let holders = map
+            .prefix(AssetInfo::native("uosmo").into())
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            holders,
+            vec![(alice, Uint128::new(1)), (bob, Uint128::new(2))]
+        );
+    }
+
+    #[test]
+    fn test_asset_list_store_add_and_subtract() {
+        let mut storage = MockStorage::new();
+
+        BALANCES
+            .add_many(
+                &mut storage,
+                &vec![Coin::new(100, "uosmo"), Coin::new(50, "uatom")].into(),
+            )
+            .unwrap();
+        assert_eq!(
+            BALANCES
+                .add(
+                    &mut storage,
+                    &Asset::new(AssetInfo::cw20(Addr::unchecked("apollo")), 10u128)
+                )
+                .unwrap(),
+            Uint128::new(10)
+        );
+        assert_eq!(
+            BALANCES
+                .subtract(&mut storage, &Asset::native("uosmo", 40u128))
+                .unwrap(),
+            Uint128::new(60)
+        );
+        BALANCES
+            .subtract_many(&mut storage, &vec![Coin::new(50, "uatom")].into())
+            .unwrap();
+
+        assert_eq!(
+            BALANCES
+                .load(&storage, &AssetInfo::native("uatom"))
+                .unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            BALANCES.load_all(&storage).unwrap(),
+            AssetList::from(vec![
+                Asset::new(AssetInfo::cw20(Addr::unchecked("apollo")), 10u128),
+                Asset::native("uosmo", 60u128),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_asset_list_store_subtract_too_much() {
+        let mut storage = MockStorage::new();
+        BALANCES
+            .add(&mut storage, &Asset::native("uosmo", 10u128))
+            .unwrap();
+
+        let err = BALANCES
+            .subtract(&mut storage, &Asset::native("uosmo", 11u128))
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            StdError::overflow(OverflowError::new(OverflowOperation::Sub, 10u128, 11u128))
+        );
+    }
+}
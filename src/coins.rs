@@ -1,12 +1,261 @@
-use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
-use regex::Regex;
+use cosmwasm_std::{Coin, StdError, StdResult, Uint128, Uint256};
+use thiserror::Error;
+
+mod coin256;
+mod dec_coin;
+mod denom;
+mod denom_rules;
+mod ibc;
+mod metadata;
+mod native_coins;
+mod proto;
+mod scaling;
+#[cfg(feature = "cw-storage-plus")]
+mod storage;
+
+pub use coin256::*;
+pub use dec_coin::*;
+pub use denom::*;
+pub use denom_rules::*;
+pub use ibc::*;
+pub use metadata::*;
+pub use native_coins::*;
+pub use proto::*;
+pub use scaling::*;
+#[cfg(feature = "cw-storage-plus")]
+pub use storage::*;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CoinParseError {
+    #[error("empty string")]
+    Empty,
+
+    #[error("missing amount in {0:?}")]
+    MissingAmount(String),
+
+    #[error("amount {0} does not fit in a 128-bit unsigned integer")]
+    AmountOverflow(String),
+
+    #[error("amount {0} does not fit in a 256-bit unsigned integer")]
+    Amount256Overflow(String),
+
+    #[error("invalid decimal amount {0}")]
+    InvalidDecimalAmount(String),
+
+    #[error("missing denom in {0:?}")]
+    MissingDenom(String),
+
+    #[error("invalid denom {0:?}")]
+    InvalidDenom(String),
+
+    #[error("empty coin at position {0} in coin list")]
+    EmptyListEntry(usize),
+
+    #[error("duplicate denom {0} in coin list")]
+    DuplicateDenom(String),
+
+    #[error("denoms in coin list are not sorted: {0} comes after {1}")]
+    UnsortedDenoms(String, String),
+}
+
+impl From<CoinParseError> for StdError {
+    fn from(err: CoinParseError) -> Self {
+        StdError::parse_err("Coin", err)
+    }
+}
+
+/// The reason a list of coins is not valid as Cosmos SDK `Coins`, as returned
+/// by [`validate_coins`].
+#[derive(Error, Debug, PartialEq)]
+pub enum InvalidCoinsError {
+    #[error("Invalid denom {denom:?} at position {index}")]
+    InvalidDenom { index: usize, denom: String },
+
+    #[error("Zero amount of {denom} at position {index}")]
+    ZeroAmount { index: usize, denom: String },
+
+    #[error("Duplicate denom {0}")]
+    DuplicateDenom(String),
+
+    #[error("Denoms are not sorted: {0} comes after {1}")]
+    UnsortedDenoms(String, String),
+}
+
+impl From<InvalidCoinsError> for StdError {
+    fn from(err: InvalidCoinsError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+/// Returns `10^exp`, or `None` if it does not fit in `T`, e.g. a `Uint128` or
+/// `Uint256`.
+pub(crate) fn pow10<T: TryFrom<Uint256>>(exp: u32) -> Option<T> {
+    Uint256::from(10u8).checked_pow(exp).ok()?.try_into().ok()
+}
+
+/// Split a string in format {amount}{denom} into its amount and denom parts.
+/// The amount part consists of all leading characters for which
+/// `is_amount_char` returns true.
+pub(crate) fn split_amount_and_denom(
+    s: &str,
+    is_amount_char: impl Fn(char) -> bool,
+) -> Result<(&str, &str), CoinParseError> {
+    if s.is_empty() {
+        return Err(CoinParseError::Empty);
+    }
+
+    let idx = s
+        .char_indices()
+        .find(|(_, c)| !is_amount_char(*c))
+        .map(|(idx, _)| idx)
+        .unwrap_or(s.len());
+    let (amount, denom) = s.split_at(idx);
+
+    if amount.is_empty() {
+        return Err(CoinParseError::MissingAmount(s.to_string()));
+    }
+    if denom.is_empty() {
+        return Err(CoinParseError::MissingDenom(s.to_string()));
+    }
+    validate_denom(denom).map_err(|_| CoinParseError::InvalidDenom(denom.to_string()))?;
+
+    Ok((amount, denom))
+}
+
+/// Parse a coin from a string in format {amount}{denom}, e.g. `100uosmo`.
+///
+/// ### Errors
+/// Returns a `StdError::ParseErr` wrapping a [`CoinParseError`] if the string
+/// is empty, has no amount or denom, if the amount does not fit in a `u128`
+/// or if the denom is not valid according to [`validate_denom`].
+pub fn try_coin_from_str(s: &str) -> StdResult<Coin> {
+    let (amount, denom) = split_amount_and_denom(s, |c| c.is_ascii_digit())?;
+    let amount = amount
+        .parse::<u128>()
+        .map_err(|_| CoinParseError::AmountOverflow(amount.to_string()))?;
+
+    Ok(Coin {
+        denom: denom.to_string(),
+        amount: Uint128::new(amount),
+    })
+}
+
+/// Parse a comma-separated list of coins in the format used by the Cosmos SDK,
+/// e.g. `100uatom,25uosmo`. Whitespace around each coin is ignored and an
+/// empty string is parsed as an empty list.
+///
+/// ### Errors
+/// Returns a `StdError::ParseErr` wrapping a [`CoinParseError`] if any of the
+/// coins can not be parsed with [`try_coin_from_str`] or if the list contains
+/// an empty entry.
+pub fn coins_from_str(s: &str) -> StdResult<Vec<Coin>> {
+    if s.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    s.split(',')
+        .enumerate()
+        .map(|(idx, coin)| match coin.trim() {
+            "" => Err(CoinParseError::EmptyListEntry(idx).into()),
+            coin => try_coin_from_str(coin),
+        })
+        .collect()
+}
+
+/// Same as [`coins_from_str`], but also requires the coins to be sorted by
+/// denom without duplicates, as the Cosmos SDK does for `sdk.Coins`.
+pub fn coins_from_str_strict(s: &str) -> StdResult<Vec<Coin>> {
+    let coins = coins_from_str(s)?;
+
+    for pair in coins.windows(2) {
+        let (prev, next) = (&pair[0].denom, &pair[1].denom);
+        if prev == next {
+            return Err(CoinParseError::DuplicateDenom(next.clone()).into());
+        }
+        if prev > next {
+            return Err(CoinParseError::UnsortedDenoms(next.clone(), prev.clone()).into());
+        }
+    }
+
+    Ok(coins)
+}
+
+/// Validate that the coins would be accepted by the Cosmos SDK as `Coins`, e.g.
+/// in a `BankMsg::Send`: every denom is valid according to [`validate_denom`],
+/// every amount is positive and the coins are sorted by denom without
+/// duplicates.
+///
+/// ### Errors
+/// Returns the first [`InvalidCoinsError`] found. Use [`normalize_coins`] to
+/// fix the list instead.
+pub fn validate_coins(coins: &[Coin]) -> Result<(), InvalidCoinsError> {
+    for (index, coin) in coins.iter().enumerate() {
+        if validate_denom(&coin.denom).is_err() {
+            return Err(InvalidCoinsError::InvalidDenom {
+                index,
+                denom: coin.denom.clone(),
+            });
+        }
+        if coin.amount.is_zero() {
+            return Err(InvalidCoinsError::ZeroAmount {
+                index,
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    for pair in coins.windows(2) {
+        let (prev, next) = (&pair[0].denom, &pair[1].denom);
+        if prev == next {
+            return Err(InvalidCoinsError::DuplicateDenom(next.clone()));
+        }
+        if prev > next {
+            return Err(InvalidCoinsError::UnsortedDenoms(
+                next.clone(),
+                prev.clone(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalize the coins so that they pass [`validate_coins`], by merging coins
+/// with the same denom, removing zero amounts and sorting by denom.
+///
+/// ### Errors
+/// Returns an error if a denom is not valid according to [`validate_denom`],
+/// or if merging amounts overflows.
+pub fn normalize_coins(coins: &[Coin]) -> StdResult<Vec<Coin>> {
+    NativeCoins::try_from(coins).map(Into::into)
+}
+
+/// Format coins in the canonical Cosmos SDK form, i.e. sorted by denom and
+/// separated by commas without whitespace, e.g. `100uatom,25uosmo`. Coins with
+/// zero amount are left out.
+pub fn coins_to_string(coins: &[Coin]) -> String {
+    let mut coins = coins
+        .iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect::<Vec<_>>();
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
 
 /// Parse coins from string in format {amount}{denom}
+///
+/// Panics if the amount is missing or does not fit in a `u128`. Use
+/// [`try_coin_from_str`] for input that is not trusted.
 pub fn coin_from_str(s: &str) -> Coin {
     // Find index of first non-digit character
     let idx = s
         .char_indices()
-        .find(|(_, c)| !c.is_digit(10))
+        .find(|(_, c)| !c.is_ascii_digit())
         .map(|(idx, _)| idx)
         .unwrap_or(s.len());
 
@@ -19,8 +268,20 @@ pub fn coin_from_str(s: &str) -> Coin {
 
 /// Validate string as a valid CosmosSDK denom according to regex
 /// `r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$"`. See https://github.com/cosmos/cosmos-sdk/blob/7728516abfab950dc7a9120caad4870f1f962df5/types/coin.go#L865-L867
+///
+/// Uses the [`DenomRules::COSMOS_SDK`] preset. Use [`DenomRules::validate`]
+/// to validate denoms for chains with other rules.
 pub fn validate_denom(input: &str) -> StdResult<()> {
-    let re = Regex::new(r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$").unwrap();
+    DenomRules::COSMOS_SDK.validate(input)
+}
+
+/// Validate string as a valid CosmosSDK denom using the regex
+/// `r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$"`. Behaves exactly like
+/// [`validate_denom`], which should be preferred since it does not need the
+/// `regex` crate.
+#[cfg(feature = "regex")]
+pub fn validate_denom_regex(input: &str) -> StdResult<()> {
+    let re = regex::Regex::new(DENOM_REGEX).unwrap();
 
     if re.is_match(input) {
         Ok(())
@@ -31,6 +292,9 @@ pub fn validate_denom(input: &str) -> StdResult<()> {
     }
 }
 
+#[cfg(any(feature = "regex", test))]
+const DENOM_REGEX: &str = r"^[a-zA-Z][a-zA-Z0-9/:._-]{2,127}$";
+
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
@@ -45,6 +309,75 @@ mod tests {
         assert_eq!(coin.denom, "gamm/pool/1");
     }
 
+    #[test_case("100uosmo" => Ok(Coin::new(100, "uosmo")); "valid coin")]
+    #[test_case("0uosmo" => Ok(Coin::new(0, "uosmo")); "zero amount")]
+    #[test_case("100000000000000000000gamm/pool/1" => Ok(Coin::new(100000000000000000000, "gamm/pool/1")); "pool share")]
+    #[test_case("340282366920938463463374607431768211455uosmo" => Ok(Coin::new(u128::MAX, "uosmo")); "u128 max")]
+    #[test_case("" => Err(CoinParseError::Empty.into()); "empty string")]
+    #[test_case("uosmo" => Err(CoinParseError::MissingAmount("uosmo".to_string()).into()); "no digits")]
+    #[test_case("100" => Err(CoinParseError::MissingDenom("100".to_string()).into()); "no denom")]
+    #[test_case("340282366920938463463374607431768211456uosmo" => Err(CoinParseError::AmountOverflow("340282366920938463463374607431768211456".to_string()).into()); "u128 max plus one")]
+    #[test_case("100u" => Err(CoinParseError::InvalidDenom("u".to_string()).into()); "denom too short")]
+    #[test_case("100 uosmo" => Err(CoinParseError::InvalidDenom(" uosmo".to_string()).into()); "space between amount and denom")]
+    #[test_case("-100uosmo" => Err(CoinParseError::MissingAmount("-100uosmo".to_string()).into()); "negative amount")]
+    fn test_try_coin_from_str(input: &str) -> StdResult<Coin> {
+        try_coin_from_str(input)
+    }
+
+    #[test_case("" => Ok(vec![]); "empty string")]
+    #[test_case("  " => Ok(vec![]); "only whitespace")]
+    #[test_case("100uatom" => Ok(vec![Coin::new(100, "uatom")]); "single coin")]
+    #[test_case("100uatom,25ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2" => Ok(vec![Coin::new(100, "uatom"), Coin::new(25, "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2")]); "event amount attribute")]
+    #[test_case(" 100uatom , 25uosmo " => Ok(vec![Coin::new(100, "uatom"), Coin::new(25, "uosmo")]); "with whitespace")]
+    #[test_case("25uosmo,100uatom" => Ok(vec![Coin::new(25, "uosmo"), Coin::new(100, "uatom")]); "unsorted")]
+    #[test_case("1uosmo,2uosmo" => Ok(vec![Coin::new(1, "uosmo"), Coin::new(2, "uosmo")]); "duplicate")]
+    #[test_case("100uatom,,25uosmo" => Err(CoinParseError::EmptyListEntry(1).into()); "empty entry")]
+    #[test_case("100uatom," => Err(CoinParseError::EmptyListEntry(1).into()); "trailing comma")]
+    #[test_case("100uatom,uosmo" => Err(CoinParseError::MissingAmount("uosmo".to_string()).into()); "invalid coin")]
+    fn test_coins_from_str(input: &str) -> StdResult<Vec<Coin>> {
+        coins_from_str(input)
+    }
+
+    #[test_case("100uatom, 25uosmo" => Ok(vec![Coin::new(100, "uatom"), Coin::new(25, "uosmo")]); "sorted")]
+    #[test_case("25uosmo,100uatom" => Err(CoinParseError::UnsortedDenoms("uatom".to_string(), "uosmo".to_string()).into()); "unsorted")]
+    #[test_case("1uosmo,2uosmo" => Err(CoinParseError::DuplicateDenom("uosmo".to_string()).into()); "duplicate")]
+    #[test_case("1IBC/ABC,1ibc/ABC" => Ok(vec![Coin::new(1, "IBC/ABC"), Coin::new(1, "ibc/ABC")]); "uppercase sorts first")]
+    fn test_coins_from_str_strict(input: &str) -> StdResult<Vec<Coin>> {
+        coins_from_str_strict(input)
+    }
+
+    #[test_case(vec![] => Ok(()); "empty")]
+    #[test_case(vec![Coin::new(1, "uatom"), Coin::new(2, "uosmo")] => Ok(()); "valid")]
+    #[test_case(vec![Coin::new(1, "uatom"), Coin::new(2, "u")] => Err(InvalidCoinsError::InvalidDenom { index: 1, denom: "u".to_string() }); "invalid denom")]
+    #[test_case(vec![Coin::new(0, "uatom"), Coin::new(2, "uosmo")] => Err(InvalidCoinsError::ZeroAmount { index: 0, denom: "uatom".to_string() }); "zero amount")]
+    #[test_case(vec![Coin::new(1, "uosmo"), Coin::new(2, "uosmo")] => Err(InvalidCoinsError::DuplicateDenom("uosmo".to_string())); "duplicate denom")]
+    #[test_case(vec![Coin::new(2, "uosmo"), Coin::new(1, "uatom")] => Err(InvalidCoinsError::UnsortedDenoms("uatom".to_string(), "uosmo".to_string())); "unsorted")]
+    fn test_validate_coins(coins: Vec<Coin>) -> Result<(), InvalidCoinsError> {
+        validate_coins(&coins)
+    }
+
+    #[test_case(vec![Coin::new(2, "uosmo"), Coin::new(0, "uion"), Coin::new(1, "uatom"), Coin::new(3, "uosmo")] => Ok(vec![Coin::new(1, "uatom"), Coin::new(5, "uosmo")]); "normalizes")]
+    #[test_case(vec![Coin::new(2, "u")] => Err(StdError::generic_err("Provided string is not a valid CosmosSDK denom.")); "invalid denom")]
+    fn test_normalize_coins(coins: Vec<Coin>) -> StdResult<Vec<Coin>> {
+        let normalized = normalize_coins(&coins)?;
+        assert_eq!(validate_coins(&normalized), Ok(()));
+        Ok(normalized)
+    }
+
+    #[test_case(vec![] => ""; "empty")]
+    #[test_case(vec![Coin::new(25, "uosmo"), Coin::new(100, "uatom")] => "100uatom,25uosmo"; "sorts by denom")]
+    #[test_case(vec![Coin::new(0, "uatom"), Coin::new(25, "uosmo")] => "25uosmo"; "skips zero amounts")]
+    fn test_coins_to_string(coins: Vec<Coin>) -> String {
+        coins_to_string(&coins)
+    }
+
+    #[test]
+    fn test_coins_to_string_roundtrip() {
+        let s = "100gamm/pool/1,5ibc/C140AFD542AE77BD7DCC83F13FDD8C5E5BB8C4929785E6EC2F4C636F98F17901,25uosmo";
+
+        assert_eq!(coins_to_string(&coins_from_str_strict(s).unwrap()), s);
+    }
+
     #[test_case("gamm/pool/1" => Ok(()); "valid osmosis LP denom")]
     #[test_case("uatom" => Ok(()); "valid uatom denom")]
     #[test_case("ibc/C140AFD542AE77BD7DCC83F13FDD8C5E5BB8C4929785E6EC2F4C636F98F17901" => Ok(()); "valid IBC denom")]
@@ -64,4 +397,40 @@ mod tests {
     fn test_validate_denom(input: &str) -> StdResult<()> {
         validate_denom(input)
     }
+
+    /// Returns whether the denom regex from the Cosmos SDK matches `input`.
+    fn regex_matches(input: &str) -> bool {
+        regex::Regex::new(DENOM_REGEX).unwrap().is_match(input)
+    }
+
+    #[test_case("uosmo"; "base denom")]
+    #[test_case("ibc/C140AFD542AE77BD7DCC83F13FDD8C5E5BB8C4929785E6EC2F4C636F98F17901"; "ibc denom")]
+    #[test_case("uatom\n"; "trailing newline")]
+    #[test_case("\nuatom"; "leading newline")]
+    #[test_case("uätom"; "non-ascii letter")]
+    #[test_case("äbc"; "non-ascii first letter")]
+    #[test_case("ab"; "two characters")]
+    #[test_case("aé"; "two characters three bytes")]
+    #[test_case(""; "empty")]
+    #[test_case("a\u{0}b"; "null byte")]
+    fn test_validate_denom_matches_regex(input: &str) {
+        assert_eq!(validate_denom(input).is_ok(), regex_matches(input));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn proptest_validate_denom_matches_regex(input in "\\PC{0,140}") {
+            proptest::prop_assert_eq!(validate_denom(&input).is_ok(), regex_matches(&input));
+        }
+
+        #[test]
+        fn proptest_validate_denom_matches_regex_near_valid(input in "[a-zA-Z0-9/:._ -]{0,130}") {
+            proptest::prop_assert_eq!(validate_denom(&input).is_ok(), regex_matches(&input));
+        }
+
+        #[test]
+        fn proptest_valid_denoms_are_accepted(input in "[a-zA-Z][a-zA-Z0-9/:._-]{2,127}") {
+            proptest::prop_assert!(validate_denom(&input).is_ok());
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, StdError, StdResult, Uint128, Uint256};
+
+use super::{split_amount_and_denom, CoinParseError};
+
+/// A coin with a `Uint256` amount, for tokens whose amounts can exceed a
+/// `u128`, such as bridged EVM tokens with 18 decimals.
+#[cw_serde]
+pub struct Coin256 {
+    pub denom: String,
+    pub amount: Uint256,
+}
+
+impl Coin256 {
+    pub fn new(amount: impl Into<Uint256>, denom: impl Into<String>) -> Self {
+        Self {
+            denom: denom.into(),
+            amount: amount.into(),
+        }
+    }
+}
+
+impl From<Coin> for Coin256 {
+    fn from(coin: Coin) -> Self {
+        Self::new(coin.amount, coin.denom)
+    }
+}
+
+impl TryFrom<Coin256> for Coin {
+    type Error = StdError;
+
+    /// Fails if the amount does not fit in a `Uint128`.
+    fn try_from(coin: Coin256) -> StdResult<Self> {
+        Ok(Coin {
+            amount: Uint128::try_from(coin.amount)?,
+            denom: coin.denom,
+        })
+    }
+}
+
+impl fmt::Display for Coin256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl FromStr for Coin256 {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        coin256_from_str(s)
+    }
+}
+
+/// Parse a `Coin256` from a string in format {amount}{denom}, e.g.
+/// `100000000000000000000gamm/pool/1`.
+///
+/// ### Errors
+/// Returns a `StdError::ParseErr` wrapping a [`CoinParseError`] if the string
+/// is empty, has no amount or denom, if the amount does not fit in a
+/// `Uint256` or if the denom is not valid according to
+/// [`validate_denom`](super::validate_denom).
+pub fn coin256_from_str(s: &str) -> StdResult<Coin256> {
+    let to_std_err = |e: CoinParseError| StdError::parse_err("Coin256", e);
+
+    let (amount, denom) = split_amount_and_denom(s, |c| c.is_ascii_digit()).map_err(to_std_err)?;
+    let amount = Uint256::from_str(amount)
+        .map_err(|_| to_std_err(CoinParseError::Amount256Overflow(amount.to_string())))?;
+
+    Ok(Coin256::new(amount, denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::ConversionOverflowError;
+    use test_case::test_case;
+
+    const UINT256_MAX: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    #[test_case("100uosmo" => Ok(Coin256::new(100u128, "uosmo")); "small amount")]
+    #[test_case("100000000000000000000gamm/pool/1" => Ok(Coin256::new(100000000000000000000u128, "gamm/pool/1")); "pool share")]
+    #[test_case("340282366920938463463374607431768211456wei" => Ok(Coin256::new(Uint256::from(u128::MAX) + Uint256::one(), "wei")); "u128 max plus one")]
+    #[test_case(&format!("{}wei", UINT256_MAX) => Ok(Coin256::new(Uint256::MAX, "wei")); "uint256 max")]
+    #[test_case(&format!("{}0wei", UINT256_MAX) => Err(StdError::parse_err("Coin256", CoinParseError::Amount256Overflow(format!("{}0", UINT256_MAX)))); "uint256 overflow")]
+    #[test_case("" => Err(StdError::parse_err("Coin256", CoinParseError::Empty)); "empty string")]
+    #[test_case("wei" => Err(StdError::parse_err("Coin256", CoinParseError::MissingAmount("wei".to_string()))); "missing amount")]
+    #[test_case("100" => Err(StdError::parse_err("Coin256", CoinParseError::MissingDenom("100".to_string()))); "missing denom")]
+    #[test_case("100u" => Err(StdError::parse_err("Coin256", CoinParseError::InvalidDenom("u".to_string()))); "invalid denom")]
+    fn test_coin256_from_str(input: &str) -> StdResult<Coin256> {
+        coin256_from_str(input)
+    }
+
+    #[test]
+    fn test_coin256_display_roundtrip() {
+        let s = format!("{}wei", UINT256_MAX);
+        assert_eq!(coin256_from_str(&s).unwrap().to_string(), s);
+    }
+
+    #[test]
+    fn test_coin256_to_coin() {
+        assert_eq!(
+            Coin::try_from(Coin256::new(u128::MAX, "wei")),
+            Ok(Coin::new(u128::MAX, "wei"))
+        );
+        assert_eq!(
+            Coin::try_from(Coin256::new(
+                Uint256::from(u128::MAX) + Uint256::one(),
+                "wei"
+            )),
+            Err(StdError::from(ConversionOverflowError::new(
+                "Uint256",
+                "Uint128",
+                "340282366920938463463374607431768211456"
+            )))
+        );
+        assert_eq!(
+            Coin256::from(Coin::new(5, "uosmo")),
+            Coin256::new(5u128, "uosmo")
+        );
+    }
+}
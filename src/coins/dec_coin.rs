@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal, SignedDecimal, StdError, StdResult, Uint128};
+
+use super::{split_amount_and_denom, CoinParseError};
+
+/// 10^18, the number of atomics in one unit of a `Decimal`.
+const DECIMAL_FRACTIONAL: u128 = 1_000_000_000_000_000_000;
+
+/// How to round a decimal amount to an integer amount.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum Rounding {
+    /// Round towards zero.
+    Floor,
+    /// Round away from zero.
+    Ceil,
+    /// Round to the nearest integer, and to the nearest even integer if
+    /// exactly halfway, like `Dec.RoundInt` in the Cosmos SDK.
+    Round,
+}
+
+/// A coin with a decimal amount, like `DecCoin` in the Cosmos SDK.
+#[cw_serde]
+pub struct DecCoin {
+    pub denom: String,
+    pub amount: Decimal,
+}
+
+impl DecCoin {
+    pub fn new(amount: Decimal, denom: impl Into<String>) -> Self {
+        Self {
+            denom: denom.into(),
+            amount,
+        }
+    }
+
+    /// Convert to a `Coin`, rounding the amount with the given `Rounding`.
+    ///
+    /// ### Returns
+    /// Returns the `Coin` together with the truncated remainder, i.e. the
+    /// decimal amount minus the amount of the `Coin`. The remainder is negative
+    /// if the amount was rounded up.
+    pub fn to_coin(&self, rounding: Rounding) -> (Coin, SignedDecimal) {
+        let floor = self.amount.to_uint_floor();
+        let fraction = self.amount.atomics().u128() % DECIMAL_FRACTIONAL;
+        let half = DECIMAL_FRACTIONAL / 2;
+
+        let round_up = match rounding {
+            Rounding::Floor => false,
+            Rounding::Ceil => fraction > 0,
+            Rounding::Round => fraction > half || (fraction == half && floor.u128() % 2 == 1),
+        };
+
+        // The remainder is always less than one in absolute value, so it fits
+        // in a SignedDecimal.
+        let (amount, remainder) = if round_up {
+            (
+                floor + Uint128::one(),
+                fraction as i128 - DECIMAL_FRACTIONAL as i128,
+            )
+        } else {
+            (floor, fraction as i128)
+        };
+
+        (
+            Coin {
+                denom: self.denom.clone(),
+                amount,
+            },
+            SignedDecimal::raw(remainder),
+        )
+    }
+}
+
+impl TryFrom<Coin> for DecCoin {
+    type Error = StdError;
+
+    /// Fails if the amount is too large to fit in a `Decimal`.
+    fn try_from(coin: Coin) -> StdResult<Self> {
+        let amount = Decimal::from_atomics(coin.amount, 0)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        Ok(Self::new(amount, coin.denom))
+    }
+}
+
+impl fmt::Display for DecCoin {
+    /// Formats the coin in the canonical Cosmos SDK form, with exactly 18
+    /// decimal places, e.g. `12.345000000000000000uatom`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let atomics = self.amount.atomics().u128();
+        write!(
+            f,
+            "{}.{:018}{}",
+            atomics / DECIMAL_FRACTIONAL,
+            atomics % DECIMAL_FRACTIONAL,
+            self.denom
+        )
+    }
+}
+
+impl FromStr for DecCoin {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        dec_coin_from_str(s)
+    }
+}
+
+/// Parse a `DecCoin` from a string in format {amount}{denom}, where the amount
+/// is a decimal with at most 18 decimal places, e.g. `12.345uatom` or
+/// `12.345000000000000000uatom`.
+///
+/// ### Errors
+/// Returns a `StdError::ParseErr` wrapping a [`CoinParseError`] if the string
+/// is empty, has no amount or denom, if the amount is not a valid `Decimal` or
+/// if the denom is not valid according to
+/// [`validate_denom`](super::validate_denom).
+pub fn dec_coin_from_str(s: &str) -> StdResult<DecCoin> {
+    let to_std_err = |e: CoinParseError| StdError::parse_err("DecCoin", e);
+
+    let (amount, denom) =
+        split_amount_and_denom(s, |c| c.is_ascii_digit() || c == '.').map_err(to_std_err)?;
+    let amount = Decimal::from_str(amount)
+        .map_err(|_| to_std_err(CoinParseError::InvalidDecimalAmount(amount.to_string())))?;
+
+    Ok(DecCoin::new(amount, denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("12.345000000000000000uatom" => Ok(DecCoin::new(Decimal::from_str("12.345").unwrap(), "uatom")); "canonical form")]
+    #[test_case("12.345uatom" => Ok(DecCoin::new(Decimal::from_str("12.345").unwrap(), "uatom")); "short form")]
+    #[test_case("12uatom" => Ok(DecCoin::new(Decimal::from_str("12").unwrap(), "uatom")); "integer")]
+    #[test_case("0.000000000000000001uatom" => Ok(DecCoin::new(Decimal::raw(1), "uatom")); "smallest amount")]
+    #[test_case("" => Err(StdError::parse_err("DecCoin", CoinParseError::Empty)); "empty string")]
+    #[test_case("uatom" => Err(StdError::parse_err("DecCoin", CoinParseError::MissingAmount("uatom".to_string()))); "missing amount")]
+    #[test_case("12.5" => Err(StdError::parse_err("DecCoin", CoinParseError::MissingDenom("12.5".to_string()))); "missing denom")]
+    #[test_case("1.2.3uatom" => Err(StdError::parse_err("DecCoin", CoinParseError::InvalidDecimalAmount("1.2.3".to_string()))); "two decimal points")]
+    #[test_case("0.0000000000000000001uatom" => Err(StdError::parse_err("DecCoin", CoinParseError::InvalidDecimalAmount("0.0000000000000000001".to_string()))); "too many decimals")]
+    #[test_case("12.5u" => Err(StdError::parse_err("DecCoin", CoinParseError::InvalidDenom("u".to_string()))); "invalid denom")]
+    fn test_dec_coin_from_str(input: &str) -> StdResult<DecCoin> {
+        dec_coin_from_str(input)
+    }
+
+    #[test_case("12.345" => "12.345000000000000000uatom"; "decimal")]
+    #[test_case("0" => "0.000000000000000000uatom"; "zero")]
+    #[test_case("100" => "100.000000000000000000uatom"; "integer")]
+    #[test_case("0.000000000000000001" => "0.000000000000000001uatom"; "smallest amount")]
+    fn test_dec_coin_display(amount: &str) -> String {
+        DecCoin::new(Decimal::from_str(amount).unwrap(), "uatom").to_string()
+    }
+
+    #[test_case("12.4", Rounding::Floor => (Coin::new(12, "uatom"), SignedDecimal::from_str("0.4").unwrap()); "floor")]
+    #[test_case("12.4", Rounding::Ceil => (Coin::new(13, "uatom"), SignedDecimal::from_str("-0.6").unwrap()); "ceil")]
+    #[test_case("12", Rounding::Ceil => (Coin::new(12, "uatom"), SignedDecimal::from_str("0").unwrap()); "ceil integer")]
+    #[test_case("12.4", Rounding::Round => (Coin::new(12, "uatom"), SignedDecimal::from_str("0.4").unwrap()); "round down")]
+    #[test_case("12.6", Rounding::Round => (Coin::new(13, "uatom"), SignedDecimal::from_str("-0.4").unwrap()); "round up")]
+    #[test_case("12.5", Rounding::Round => (Coin::new(12, "uatom"), SignedDecimal::from_str("0.5").unwrap()); "round half to even down")]
+    #[test_case("13.5", Rounding::Round => (Coin::new(14, "uatom"), SignedDecimal::from_str("-0.5").unwrap()); "round half to even up")]
+    #[test_case("0.000000000000000001", Rounding::Ceil => (Coin::new(1, "uatom"), SignedDecimal::from_str("-0.999999999999999999").unwrap()); "ceil smallest amount")]
+    fn test_dec_coin_to_coin(amount: &str, rounding: Rounding) -> (Coin, SignedDecimal) {
+        DecCoin::new(Decimal::from_str(amount).unwrap(), "uatom").to_coin(rounding)
+    }
+
+    #[test]
+    fn test_dec_coin_try_from_coin() {
+        let max = Decimal::MAX.to_uint_floor();
+        let dec_coin = DecCoin::try_from(Coin::new(max.u128(), "uatom")).unwrap();
+
+        assert_eq!(
+            dec_coin.to_coin(Rounding::Floor),
+            (Coin::new(max.u128(), "uatom"), SignedDecimal::zero())
+        );
+        assert!(DecCoin::try_from(Coin::new(max.u128() + 1, "uatom")).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use cosmwasm_std::{StdError, StdResult};
+
+use super::validate_denom;
+
+/// The kind of a native denom, as returned by [`classify_denom`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DenomKind {
+    /// A base denom without any path, e.g. `uosmo`.
+    Base(String),
+    /// An IBC voucher denom in format `ibc/{hash}`, where `hash` is the
+    /// 64-character hex encoded SHA-256 hash of the denom trace.
+    Ibc { hash: String },
+    /// A token factory denom in format `factory/{creator}/{subdenom}`.
+    TokenFactory { creator: String, subdenom: String },
+    /// An Osmosis GAMM pool share denom in format `gamm/pool/{pool_id}`.
+    GammPool { pool_id: u64 },
+    /// Any other valid denom.
+    Other(String),
+}
+
+impl fmt::Display for DenomKind {
+    /// Formats the denom back into its string form.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DenomKind::Base(denom) | DenomKind::Other(denom) => write!(f, "{}", denom),
+            DenomKind::Ibc { hash } => write!(f, "ibc/{}", hash),
+            DenomKind::TokenFactory { creator, subdenom } => {
+                write!(f, "factory/{}/{}", creator, subdenom)
+            }
+            DenomKind::GammPool { pool_id } => write!(f, "gamm/pool/{}", pool_id),
+        }
+    }
+}
+
+/// Returns true if `hash` is a 64-character hex string, i.e. a hex encoded
+/// SHA-256 hash.
+pub(crate) fn is_hex_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Classify a denom by its format.
+///
+/// ### Errors
+/// Returns an error if the denom is not valid according to [`validate_denom`].
+/// Returns an error if the denom starts with `ibc/`, `factory/` or
+/// `gamm/pool/` but the rest of it is not in the expected format.
+pub fn classify_denom(denom: &str) -> StdResult<DenomKind> {
+    validate_denom(denom)?;
+
+    if let Some(hash) = denom.strip_prefix("ibc/") {
+        if !is_hex_hash(hash) {
+            return Err(StdError::generic_err(format!(
+                "Invalid IBC denom {}: hash must be 64 hex characters",
+                denom
+            )));
+        }
+        return Ok(DenomKind::Ibc {
+            hash: hash.to_string(),
+        });
+    }
+
+    if let Some(rest) = denom.strip_prefix("factory/") {
+        return match rest.split_once('/') {
+            Some((creator, subdenom)) if !creator.is_empty() => Ok(DenomKind::TokenFactory {
+                creator: creator.to_string(),
+                subdenom: subdenom.to_string(),
+            }),
+            _ => Err(StdError::generic_err(format!(
+                "Invalid token factory denom {}: expected factory/{{creator}}/{{subdenom}}",
+                denom
+            ))),
+        };
+    }
+
+    if let Some(pool_id) = denom.strip_prefix("gamm/pool/") {
+        return match pool_id.parse::<u64>() {
+            Ok(pool_id) => Ok(DenomKind::GammPool { pool_id }),
+            Err(_) => Err(StdError::generic_err(format!(
+                "Invalid GAMM pool denom {}: pool id must be an unsigned integer",
+                denom
+            ))),
+        };
+    }
+
+    if denom.contains('/') {
+        Ok(DenomKind::Other(denom.to_string()))
+    } else {
+        Ok(DenomKind::Base(denom.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("uosmo" => Ok(DenomKind::Base("uosmo".to_string())); "base denom")]
+    #[test_case("cw20:juno1abc" => Ok(DenomKind::Base("cw20:juno1abc".to_string())); "base denom with colon")]
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2" => Ok(DenomKind::Ibc { hash: "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string() }); "ibc denom")]
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB" => Err(StdError::generic_err("Invalid IBC denom ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB: hash must be 64 hex characters")); "ibc denom hash too short")]
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EBG" => Err(StdError::generic_err("Invalid IBC denom ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EBG: hash must be 64 hex characters")); "ibc denom hash not hex")]
+    #[test_case("factory/osmo1g3kmqpp8608szfp0pdag3r6z85npph7wmccat8lgl3mp407kv73qlj7qwp/VaultToken/1/14d/ATOM/OSMO" => Ok(DenomKind::TokenFactory { creator: "osmo1g3kmqpp8608szfp0pdag3r6z85npph7wmccat8lgl3mp407kv73qlj7qwp".to_string(), subdenom: "VaultToken/1/14d/ATOM/OSMO".to_string() }); "token factory denom")]
+    #[test_case("factory/osmo1abc" => Err(StdError::generic_err("Invalid token factory denom factory/osmo1abc: expected factory/{creator}/{subdenom}")); "token factory denom without subdenom")]
+    #[test_case("factory//uatom" => Err(StdError::generic_err("Invalid token factory denom factory//uatom: expected factory/{creator}/{subdenom}")); "token factory denom without creator")]
+    #[test_case("gamm/pool/1" => Ok(DenomKind::GammPool { pool_id: 1 }); "gamm pool denom")]
+    #[test_case("gamm/pool/abc" => Err(StdError::generic_err("Invalid GAMM pool denom gamm/pool/abc: pool id must be an unsigned integer")); "gamm pool denom with invalid id")]
+    #[test_case("cl/pool/1" => Ok(DenomKind::Other("cl/pool/1".to_string())); "other denom")]
+    #[test_case("u" => Err(StdError::generic_err("Provided string is not a valid CosmosSDK denom.")); "invalid denom")]
+    fn test_classify_denom(denom: &str) -> StdResult<DenomKind> {
+        classify_denom(denom)
+    }
+
+    #[test_case("uosmo"; "base denom")]
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"; "ibc denom")]
+    #[test_case("factory/osmo1abc/VaultToken/1"; "token factory denom")]
+    #[test_case("gamm/pool/42"; "gamm pool denom")]
+    #[test_case("cl/pool/1"; "other denom")]
+    fn test_denom_kind_display_roundtrip(denom: &str) {
+        assert_eq!(classify_denom(denom).unwrap().to_string(), denom);
+    }
+}
@@ -0,0 +1,160 @@
+use cosmwasm_std::{StdError, StdResult};
+
+/// Rules that a native denom must satisfy. Chains can set their own denom
+/// rules, so pick the preset matching the chain the contract is deployed on,
+/// or build custom rules.
+///
+/// A denom is valid if it matches any of `extra_patterns`, or if its length is
+/// within `min_length..=max_length`, its first character satisfies
+/// `is_valid_first_char` and all other characters satisfy `is_valid_char`.
+#[derive(Clone, Copy, Debug)]
+pub struct DenomRules {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub is_valid_first_char: fn(char) -> bool,
+    pub is_valid_char: fn(char) -> bool,
+    /// Denom formats that are accepted in addition to the rules above.
+    pub extra_patterns: &'static [fn(&str) -> bool],
+}
+
+impl DenomRules {
+    /// The rules of the Cosmos SDK since v0.43, i.e. the regex
+    /// `[a-zA-Z][a-zA-Z0-9/:._-]{2,127}`.
+    pub const COSMOS_SDK: DenomRules = DenomRules {
+        min_length: 3,
+        max_length: 128,
+        is_valid_first_char: |c| c.is_ascii_alphabetic(),
+        is_valid_char: |c| c.is_ascii_alphanumeric() || "/:._-".contains(c),
+        extra_patterns: &[],
+    };
+
+    /// The rules of Cosmos SDK v0.40 to v0.42, i.e. the regex
+    /// `[a-zA-Z][a-zA-Z0-9/]{2,127}`.
+    pub const COSMOS_SDK_V040: DenomRules = DenomRules {
+        min_length: 3,
+        max_length: 128,
+        is_valid_first_char: |c| c.is_ascii_alphabetic(),
+        is_valid_char: |c| c.is_ascii_alphanumeric() || c == '/',
+        extra_patterns: &[],
+    };
+
+    /// The rules of Cosmos SDK v0.39 and earlier, i.e. the regex
+    /// `[a-z][a-z0-9/]{2,63}`.
+    pub const COSMOS_SDK_V039: DenomRules = DenomRules {
+        min_length: 3,
+        max_length: 64,
+        is_valid_first_char: |c| c.is_ascii_lowercase(),
+        is_valid_char: |c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '/',
+        extra_patterns: &[],
+    };
+
+    /// The rules of Injective, which uses the Cosmos SDK rules. Bridged
+    /// Ethereum tokens have denoms in format `peggy0x{address}`.
+    pub const INJECTIVE: DenomRules = Self::COSMOS_SDK;
+
+    /// The rules of Kujira, which uses the Cosmos SDK rules.
+    pub const KUJIRA: DenomRules = Self::COSMOS_SDK;
+
+    /// The rules of Sei, which uses the Cosmos SDK rules. Pointer denoms of
+    /// CW20 and ERC20 tokens have format `cw20/{address}` and
+    /// `erc20/{address}`.
+    pub const SEI: DenomRules = Self::COSMOS_SDK;
+
+    /// Returns true if `denom` is valid according to the rules.
+    pub fn is_valid(&self, denom: &str) -> bool {
+        if self.extra_patterns.iter().any(|matches| matches(denom)) {
+            return true;
+        }
+
+        let mut chars = denom.chars();
+        (self.min_length..=self.max_length).contains(&denom.chars().count())
+            && chars.next().is_some_and(self.is_valid_first_char)
+            && chars.all(self.is_valid_char)
+    }
+
+    /// Validate `denom` according to the rules.
+    pub fn validate(&self, denom: &str) -> StdResult<()> {
+        if self.is_valid(denom) {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(
+                "Provided string is not a valid CosmosSDK denom.",
+            ))
+        }
+    }
+}
+
+impl Default for DenomRules {
+    fn default() -> Self {
+        Self::COSMOS_SDK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(DenomRules::INJECTIVE, "inj" => true; "injective native")]
+    #[test_case(DenomRules::INJECTIVE, "peggy0xdAC17F958D2ee523a2206206994597C13D831ec7" => true; "injective peggy")]
+    #[test_case(DenomRules::INJECTIVE, "factory/inj1hkhdaj2a2clmq5jq6mspsggqs32vynpk228q3r/atom" => true; "injective token factory")]
+    #[test_case(DenomRules::INJECTIVE, "0xdAC17F958D2ee523a2206206994597C13D831ec7" => false; "injective bare address")]
+    #[test_case(DenomRules::KUJIRA, "ukuji" => true; "kujira native")]
+    #[test_case(DenomRules::KUJIRA, "factory/kujira1qk00h5atutpsv900x202pxx42npjr9thg58dnqpa72f2p7m2luase444a7/uusk" => true; "kujira token factory")]
+    #[test_case(DenomRules::KUJIRA, "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2" => true; "kujira ibc")]
+    #[test_case(DenomRules::SEI, "usei" => true; "sei native")]
+    #[test_case(DenomRules::SEI, "factory/sei1466nf3zuxpya8q9emxukd7vftaf6h4psr0a07srl5zw74zh84yjqpeheyc/uusdc" => true; "sei token factory")]
+    #[test_case(DenomRules::SEI, "cw20/sei1hrndqntlvtmx2kepr0zsfgr7nzjptcc72cr4ppk4yav58vvy7v3s4er8ed" => true; "sei cw20 pointer")]
+    #[test_case(DenomRules::SEI, "erc20/0x3894085Ef7Ff0f0aeDf52E2A2704928d1Ec074F1" => true; "sei erc20 pointer")]
+    fn test_chain_presets(rules: DenomRules, denom: &str) -> bool {
+        rules.is_valid(denom)
+    }
+
+    #[test_case(DenomRules::COSMOS_SDK, "test:test/test-test.test_test" => true; "sdk separators")]
+    #[test_case(DenomRules::COSMOS_SDK_V040, "test:test/test-test.test_test" => false; "v040 separators")]
+    #[test_case(DenomRules::COSMOS_SDK_V040, "gamm/pool/1" => true; "v040 slash")]
+    #[test_case(DenomRules::COSMOS_SDK_V040, "IBC/C140AFD542AE77BD7DCC83F13FDD8C5E5BB8C4929785E6EC2F4C636F98F17901" => true; "v040 uppercase")]
+    #[test_case(DenomRules::COSMOS_SDK_V039, "IBC/C140AFD542AE77BD7DCC83F13FDD8C5E5BB8C4929785E6EC2F4C636F98F17901" => false; "v039 uppercase")]
+    #[test_case(DenomRules::COSMOS_SDK_V039, "uluna" => true; "v039 lowercase")]
+    #[test_case(DenomRules::COSMOS_SDK_V039, &"a".repeat(65) => false; "v039 too long")]
+    #[test_case(DenomRules::COSMOS_SDK, &"a".repeat(65) => true; "sdk longer than v039 max")]
+    #[test_case(DenomRules::COSMOS_SDK, "" => false; "empty")]
+    fn test_is_valid(rules: DenomRules, denom: &str) -> bool {
+        rules.is_valid(denom)
+    }
+
+    fn is_short_denom(denom: &str) -> bool {
+        denom == "ux"
+    }
+
+    #[test]
+    fn test_extra_patterns() {
+        let rules = DenomRules {
+            extra_patterns: &[is_short_denom],
+            ..DenomRules::COSMOS_SDK
+        };
+
+        assert!(rules.is_valid("ux"));
+        assert!(rules.is_valid("uosmo"));
+        assert!(!rules.is_valid("uy"));
+        assert!(!DenomRules::COSMOS_SDK.is_valid("ux"));
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = DenomRules {
+            min_length: 1,
+            max_length: 10,
+            is_valid_first_char: |c| c == 'u',
+            ..DenomRules::default()
+        };
+
+        assert_eq!(rules.validate("u"), Ok(()));
+        assert_eq!(
+            rules.validate("atom"),
+            Err(StdError::generic_err(
+                "Provided string is not a valid CosmosSDK denom."
+            ))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdError, StdResult};
+use sha2::{Digest, Sha256};
+
+/// Returns the `ibc/{hash}` denom of a token with base denom `base_denom` that
+/// was transferred over the channels in `path`, e.g.
+/// `ibc_denom("transfer/channel-0", "uatom")`. The hash is the uppercase hex
+/// encoded SHA-256 hash of `{path}/{base_denom}`.
+///
+/// Neither argument is validated, use [`DenomTrace::validate`] for that.
+pub fn ibc_denom(path: &str, base_denom: &str) -> String {
+    let hash = Sha256::digest(format!("{}/{}", path, base_denom).as_bytes());
+    let hex = hash
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<String>();
+    format!("ibc/{}", hex)
+}
+
+/// The trace of a token transferred over IBC, like `DenomTrace` in the ibc-go
+/// transfer module.
+#[cw_serde]
+pub struct DenomTrace {
+    /// The chain of port and channel identifiers the token was transferred
+    /// over, e.g. `transfer/channel-0`. Empty if the token is native to the
+    /// chain.
+    pub path: String,
+    /// The denom of the token on the chain it is native to.
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    pub fn new(path: impl Into<String>, base_denom: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            base_denom: base_denom.into(),
+        }
+    }
+
+    /// Returns the denom of the token on the current chain, i.e. the base denom
+    /// if the path is empty and the `ibc/{hash}` denom otherwise.
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            ibc_denom(&self.path, &self.base_denom)
+        }
+    }
+
+    /// Validate the trace the same way as ibc-go does: the base denom must not
+    /// be blank and the path must consist of pairs of valid port and channel
+    /// identifiers.
+    pub fn validate(&self) -> StdResult<()> {
+        if self.base_denom.trim().is_empty() {
+            return Err(StdError::generic_err("Base denom of denom trace is blank"));
+        }
+        if self.path.is_empty() {
+            return Ok(());
+        }
+
+        let identifiers = self.path.split('/').collect::<Vec<_>>();
+        if identifiers.len() % 2 != 0 {
+            return Err(StdError::generic_err(format!(
+                "Denom trace path {} must consist of port and channel identifier pairs",
+                self.path
+            )));
+        }
+        for pair in identifiers.chunks(2) {
+            validate_identifier(pair[0], 2, 128, "port")?;
+            validate_identifier(pair[1], 8, 64, "channel")?;
+        }
+        Ok(())
+    }
+
+    /// Validate the trace and assert that it results in the IBC denom `denom`.
+    pub fn assert_ibc_denom(&self, denom: &str) -> StdResult<()> {
+        self.validate()?;
+        let expected = self.ibc_denom();
+        // The hash in the denom may be in lowercase
+        let matches = match (expected.strip_prefix("ibc/"), denom.strip_prefix("ibc/")) {
+            (Some(expected_hash), Some(hash)) => expected_hash.eq_ignore_ascii_case(hash),
+            _ => expected == denom,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(format!(
+                "Denom trace {}/{} results in denom {}, not {}",
+                self.path, self.base_denom, expected, denom
+            )))
+        }
+    }
+}
+
+/// Validate an IBC port or channel identifier as ibc-go does.
+fn validate_identifier(id: &str, min_len: usize, max_len: usize, kind: &str) -> StdResult<()> {
+    let valid_chars = id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "._+-#[]<>".contains(c));
+    if id.len() < min_len || id.len() > max_len || !valid_chars {
+        return Err(StdError::generic_err(format!(
+            "Invalid {} identifier {} in denom trace",
+            kind, id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("transfer/channel-0", "uatom" => "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"; "ATOM on Osmosis")]
+    #[test_case("transfer/channel-42", "ujuno" => "ibc/46B44899322F3CD854D2D46DEEF881958467CDD4B3B10086DA49296BBED94BED"; "JUNO on Osmosis")]
+    #[test_case("transfer/channel-208", "uusdc" => "ibc/D189335C6E4A68B513C10AB227BF1C1D38C746766278BA3EEB4FB14124F1D858"; "axlUSDC on Osmosis")]
+    #[test_case("transfer/channel-750", "uusdc" => "ibc/498A0751C798A0D9A389AA3691123DADA57DAA4FE165D5C75894505B876BA6E4"; "USDC on Osmosis")]
+    #[test_case("transfer/channel-141", "uosmo" => "ibc/14F9BC3E44B8A9C1BE1FB08980FAB87034C9905EF17CF2F5008FC085218811CC"; "OSMO on Cosmos Hub")]
+    #[test_case("transfer/channel-0/transfer/channel-141", "uosmo" => "ibc/34DFCA8EEDE4A14ECDA79AC56E024EE3AA497DC4D7E812DE9FD09B17D348E282"; "multi hop")]
+    fn test_ibc_denom(path: &str, base_denom: &str) -> String {
+        ibc_denom(path, base_denom)
+    }
+
+    #[test]
+    fn test_denom_trace_ibc_denom() {
+        assert_eq!(DenomTrace::new("", "uosmo").ibc_denom(), "uosmo");
+        assert_eq!(
+            DenomTrace::new("transfer/channel-0", "uatom").ibc_denom(),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test_case("", "uosmo" => Ok(()); "native token")]
+    #[test_case("transfer/channel-0", "uatom" => Ok(()); "one hop")]
+    #[test_case("transfer/channel-0/transfer/channel-141", "uosmo" => Ok(()); "two hops")]
+    #[test_case("transfer/channel-0", "gamm/pool/1" => Ok(()); "base denom with slashes")]
+    #[test_case("transfer/channel-0", " " => Err(StdError::generic_err("Base denom of denom trace is blank")); "blank base denom")]
+    #[test_case("transfer", "uatom" => Err(StdError::generic_err("Denom trace path transfer must consist of port and channel identifier pairs")); "odd number of identifiers")]
+    #[test_case("t/channel-0", "uatom" => Err(StdError::generic_err("Invalid port identifier t in denom trace")); "port too short")]
+    #[test_case("transfer/chan-0", "uatom" => Err(StdError::generic_err("Invalid channel identifier chan-0 in denom trace")); "channel too short")]
+    #[test_case("transfer/channel 0", "uatom" => Err(StdError::generic_err("Invalid channel identifier channel 0 in denom trace")); "invalid character")]
+    #[test_case("transfer//channel-0", "uatom" => Err(StdError::generic_err("Denom trace path transfer//channel-0 must consist of port and channel identifier pairs")); "empty identifier")]
+    fn test_denom_trace_validate(path: &str, base_denom: &str) -> StdResult<()> {
+        DenomTrace::new(path, base_denom).validate()
+    }
+
+    #[test_case("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2" => Ok(()); "matching denom")]
+    #[test_case("ibc/27394fb092d2eccd56123c74f36e4c1f926001ceada9ca97ea622b25f41e5eb2" => Ok(()); "matching lowercase hash")]
+    #[test_case("ibc/14F9BC3E44B8A9C1BE1FB08980FAB87034C9905EF17CF2F5008FC085218811CC" => Err(StdError::generic_err("Denom trace transfer/channel-0/uatom results in denom ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2, not ibc/14F9BC3E44B8A9C1BE1FB08980FAB87034C9905EF17CF2F5008FC085218811CC")); "other denom")]
+    fn test_denom_trace_assert_ibc_denom(denom: &str) -> StdResult<()> {
+        DenomTrace::new("transfer/channel-0", "uatom").assert_ibc_denom(denom)
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal, StdError, StdResult, Uint128};
+
+use super::{pow10, validate_denom};
+
+/// The number of decimal places of a `Decimal`.
+const DECIMAL_PLACES: u32 = 18;
+
+/// Display metadata of a native denom, like the base and display `DenomUnit`s
+/// in the Cosmos SDK bank module.
+#[cw_serde]
+pub struct DenomMetadata {
+    /// The denom used on chain, e.g. `uatom`.
+    pub base: String,
+    /// The denom shown to users, e.g. `ATOM`.
+    pub display: String,
+    /// The number of decimals of the display denom, i.e. one `display` is
+    /// `10^exponent` `base`. E.g. 6 for ATOM.
+    pub exponent: u32,
+}
+
+impl DenomMetadata {
+    pub fn new(base: impl Into<String>, display: impl Into<String>, exponent: u32) -> Self {
+        Self {
+            base: base.into(),
+            display: display.into(),
+            exponent,
+        }
+    }
+
+    /// Validate that the base denom is valid according to [`validate_denom`]
+    /// and that the display denom is not empty and has no whitespace.
+    pub fn validate(&self) -> StdResult<()> {
+        validate_denom(&self.base)?;
+        if self.display.is_empty() || self.display.contains(char::is_whitespace) {
+            return Err(StdError::generic_err(format!(
+                "Invalid display denom {:?} for {}",
+                self.display, self.base
+            )));
+        }
+        Ok(())
+    }
+
+    /// Convert an amount of the base denom to an amount of the display denom.
+    ///
+    /// ### Errors
+    /// Returns an error if the result does not fit in a `Decimal`, or if it
+    /// cannot be represented with 18 decimal places without losing precision.
+    pub fn to_display_amount(&self, amount: Uint128) -> StdResult<Decimal> {
+        if self.exponent <= DECIMAL_PLACES {
+            return Decimal::from_atomics(amount, self.exponent)
+                .map_err(|_| self.overflow_err(amount));
+        }
+        let divisor = pow10::<Uint128>(self.exponent - DECIMAL_PLACES)
+            .ok_or_else(|| self.precision_err(amount))?;
+        if !(amount % divisor).is_zero() {
+            return Err(self.precision_err(amount));
+        }
+        Ok(Decimal::raw((amount / divisor).u128()))
+    }
+
+    /// Convert an amount of the display denom to an amount of the base denom.
+    ///
+    /// ### Errors
+    /// Returns an error if the amount has more decimal places than
+    /// `exponent`, or if the result does not fit in a `Uint128`.
+    pub fn to_base_amount(&self, amount: Decimal) -> StdResult<Uint128> {
+        let atomics = amount.atomics();
+        if self.exponent >= DECIMAL_PLACES {
+            return pow10::<Uint128>(self.exponent - DECIMAL_PLACES)
+                .and_then(|factor| atomics.checked_mul(factor).ok())
+                .ok_or_else(|| {
+                    StdError::generic_err(format!(
+                        "Amount {} {} does not fit in a Uint128 in {}",
+                        amount, self.display, self.base
+                    ))
+                });
+        }
+        let divisor = pow10::<Uint128>(DECIMAL_PLACES - self.exponent).unwrap();
+        if !(atomics % divisor).is_zero() {
+            return Err(StdError::generic_err(format!(
+                "Amount {} {} has more than {} decimal places",
+                amount, self.display, self.exponent
+            )));
+        }
+        Ok(atomics / divisor)
+    }
+
+    /// Format an amount of the base denom in the display denom, e.g.
+    /// `1500000` with base `uatom` as `1.5 ATOM`.
+    pub fn format_amount(&self, amount: Uint128) -> StdResult<String> {
+        Ok(format!(
+            "{} {}",
+            self.to_display_amount(amount)?,
+            self.display
+        ))
+    }
+
+    /// Parse an amount in the display denom, e.g. `1.5 ATOM`, into a `Coin` of
+    /// the base denom.
+    pub fn parse_amount(&self, s: &str) -> StdResult<Coin> {
+        let (amount, display) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| StdError::generic_err(format!("Invalid display amount {:?}", s)))?;
+        if display.trim() != self.display {
+            return Err(StdError::generic_err(format!(
+                "Expected display denom {}, got {}",
+                self.display,
+                display.trim()
+            )));
+        }
+        let amount = Decimal::from_str(amount)?;
+        Ok(Coin {
+            denom: self.base.clone(),
+            amount: self.to_base_amount(amount)?,
+        })
+    }
+
+    fn overflow_err(&self, amount: Uint128) -> StdError {
+        StdError::generic_err(format!(
+            "Amount {}{} does not fit in a Decimal in {}",
+            amount, self.base, self.display
+        ))
+    }
+
+    fn precision_err(&self, amount: Uint128) -> StdError {
+        StdError::generic_err(format!(
+            "Amount {}{} cannot be converted to {} without losing precision",
+            amount, self.base, self.display
+        ))
+    }
+}
+
+/// An in-memory registry of [`DenomMetadata`] keyed by base denom.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DenomRegistry(BTreeMap<String, DenomMetadata>);
+
+impl DenomRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate and add the metadata to the registry, replacing any existing
+    /// metadata for the same base denom.
+    ///
+    /// ### Errors
+    /// Returns an error if the metadata is invalid, or if the display denom is
+    /// already used by another base denom.
+    pub fn register(&mut self, metadata: DenomMetadata) -> StdResult<()> {
+        metadata.validate()?;
+        if let Some(existing) = self.by_display(&metadata.display) {
+            if existing.base != metadata.base {
+                return Err(StdError::generic_err(format!(
+                    "Display denom {} is already used by {}",
+                    metadata.display, existing.base
+                )));
+            }
+        }
+        self.0.insert(metadata.base.clone(), metadata);
+        Ok(())
+    }
+
+    /// Returns the metadata of a base denom.
+    pub fn get(&self, base: &str) -> Option<&DenomMetadata> {
+        self.0.get(base)
+    }
+
+    /// Returns the metadata with the given display denom.
+    pub fn by_display(&self, display: &str) -> Option<&DenomMetadata> {
+        self.0.values().find(|metadata| metadata.display == display)
+    }
+
+    /// Format a coin in its display denom, e.g. `1.5 ATOM`. Coins without
+    /// registered metadata are formatted as `{amount}{denom}`.
+    pub fn format_coin(&self, coin: &Coin) -> StdResult<String> {
+        match self.get(&coin.denom) {
+            Some(metadata) => metadata.format_amount(coin.amount),
+            None => Ok(coin.to_string()),
+        }
+    }
+
+    /// Parse an amount in a registered display denom, e.g. `1.5 ATOM`, into a
+    /// `Coin` of the base denom.
+    pub fn parse_coin(&self, s: &str) -> StdResult<Coin> {
+        let display = s.split_whitespace().last().unwrap_or_default();
+        self.by_display(display)
+            .ok_or_else(|| StdError::generic_err(format!("Unknown display denom {:?}", display)))?
+            .parse_amount(s)
+    }
+}
+
+impl TryFrom<Vec<DenomMetadata>> for DenomRegistry {
+    type Error = StdError;
+
+    fn try_from(metadata: Vec<DenomMetadata>) -> StdResult<Self> {
+        let mut registry = Self::new();
+        for metadata in metadata {
+            registry.register(metadata)?;
+        }
+        Ok(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn atom() -> DenomMetadata {
+        DenomMetadata::new("uatom", "ATOM", 6)
+    }
+
+    #[test_case(atom(), 1_500_000 => Ok("1.5 ATOM".to_string()); "six decimals")]
+    #[test_case(atom(), 1 => Ok("0.000001 ATOM".to_string()); "smallest amount")]
+    #[test_case(atom(), 0 => Ok("0 ATOM".to_string()); "zero")]
+    #[test_case(DenomMetadata::new("uosmo", "uosmo", 0), 42 => Ok("42 uosmo".to_string()); "no decimals")]
+    #[test_case(DenomMetadata::new("wei", "ETH", 18), 1 => Ok("0.000000000000000001 ETH".to_string()); "eighteen decimals")]
+    #[test_case(DenomMetadata::new("attox", "X", 20), 1_500_000_000_000_000_000_000 => Ok("15 X".to_string()); "more than eighteen decimals")]
+    #[test_case(DenomMetadata::new("attox", "X", 20), 1 => Err(StdError::generic_err("Amount 1attox cannot be converted to X without losing precision")); "precision loss")]
+    #[test_case(DenomMetadata::new("uosmo", "OSMO", 0), u128::MAX => Err(StdError::generic_err(format!("Amount {}uosmo does not fit in a Decimal in OSMO", u128::MAX))); "overflow")]
+    fn test_format_amount(metadata: DenomMetadata, amount: u128) -> StdResult<String> {
+        metadata.format_amount(Uint128::new(amount))
+    }
+
+    #[test_case(atom(), "1.5 ATOM" => Ok(Coin::new(1_500_000, "uatom")); "six decimals")]
+    #[test_case(atom(), " 2  ATOM " => Ok(Coin::new(2_000_000, "uatom")); "extra whitespace")]
+    #[test_case(atom(), "0.0000001 ATOM" => Err(StdError::generic_err("Amount 0.0000001 ATOM has more than 6 decimal places")); "too many decimals")]
+    #[test_case(atom(), "1.5 OSMO" => Err(StdError::generic_err("Expected display denom ATOM, got OSMO")); "wrong display denom")]
+    #[test_case(atom(), "1.5ATOM" => Err(StdError::generic_err("Invalid display amount \"1.5ATOM\"")); "missing space")]
+    #[test_case(DenomMetadata::new("attox", "X", 20), "15 X" => Ok(Coin::new(1_500_000_000_000_000_000_000, "attox")); "more than eighteen decimals")]
+    fn test_parse_amount(metadata: DenomMetadata, s: &str) -> StdResult<Coin> {
+        metadata.parse_amount(s)
+    }
+
+    #[test]
+    fn test_to_base_amount_overflow() {
+        let metadata = DenomMetadata::new("attox", "X", 60);
+        assert!(metadata.to_base_amount(Decimal::one()).is_err());
+    }
+
+    #[test_case(DenomMetadata::new("u", "X", 6) => Err(StdError::generic_err("Provided string is not a valid CosmosSDK denom.")); "invalid base denom")]
+    #[test_case(DenomMetadata::new("uatom", "", 6) => Err(StdError::generic_err("Invalid display denom \"\" for uatom")); "empty display denom")]
+    #[test_case(DenomMetadata::new("uatom", "AT OM", 6) => Err(StdError::generic_err("Invalid display denom \"AT OM\" for uatom")); "display denom with space")]
+    fn test_validate(metadata: DenomMetadata) -> StdResult<()> {
+        metadata.validate()
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry =
+            DenomRegistry::try_from(vec![atom(), DenomMetadata::new("uosmo", "OSMO", 6)]).unwrap();
+
+        assert_eq!(
+            registry
+                .format_coin(&Coin::new(1_500_000, "uatom"))
+                .unwrap(),
+            "1.5 ATOM"
+        );
+        assert_eq!(
+            registry.format_coin(&Coin::new(100, "uion")).unwrap(),
+            "100uion"
+        );
+        assert_eq!(
+            registry.parse_coin("0.25 OSMO").unwrap(),
+            Coin::new(250_000, "uosmo")
+        );
+        assert_eq!(
+            registry.parse_coin("1 ION").unwrap_err(),
+            StdError::generic_err("Unknown display denom \"ION\"")
+        );
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicate_display_denom() {
+        let mut registry = DenomRegistry::try_from(vec![atom()]).unwrap();
+
+        assert_eq!(
+            registry
+                .register(DenomMetadata::new("ibc/atom", "ATOM", 6))
+                .unwrap_err(),
+            StdError::generic_err("Display denom ATOM is already used by uatom")
+        );
+        registry
+            .register(DenomMetadata::new("uatom", "ATOM", 8))
+            .unwrap();
+        assert_eq!(registry.get("uatom").unwrap().exponent, 8);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use apollo_cw_asset::{Asset, AssetInfo, AssetList};
+use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
+
+use super::{coins_to_string, validate_denom};
+use crate::iterators::TryFromIterator;
+
+/// A set of native coins that is always sorted by denom, has at most one entry
+/// per denom and no entries with zero amount, i.e. that is valid as Cosmos SDK
+/// `Coins`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NativeCoins(BTreeMap<String, Uint128>);
+
+impl NativeCoins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of denoms in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the amount of `denom` in the set, or zero if it is not in it.
+    pub fn amount_of(&self, denom: &str) -> Uint128 {
+        self.0.get(denom).copied().unwrap_or_default()
+    }
+
+    /// Returns an iterator over the coins, sorted by denom.
+    pub fn iter(&self) -> impl Iterator<Item = Coin> + '_ {
+        self.0.iter().map(|(denom, amount)| Coin {
+            denom: denom.clone(),
+            amount: *amount,
+        })
+    }
+
+    /// Returns the coins as a `Vec<Coin>`, sorted by denom.
+    pub fn to_vec(&self) -> Vec<Coin> {
+        self.iter().collect()
+    }
+
+    /// Add a coin to the set.
+    ///
+    /// ### Errors
+    /// Returns an error if the denom is not valid according to
+    /// [`validate_denom`].
+    /// Returns an error if the amount overflows.
+    pub fn checked_add_coin(&mut self, coin: &Coin) -> StdResult<()> {
+        validate_denom(&coin.denom)?;
+        if coin.amount.is_zero() {
+            return Ok(());
+        }
+        let amount = self.amount_of(&coin.denom).checked_add(coin.amount)?;
+        self.0.insert(coin.denom.clone(), amount);
+        Ok(())
+    }
+
+    /// Subtract a coin from the set, removing the denom if nothing is left.
+    ///
+    /// ### Errors
+    /// Returns an error if the set has less than `coin.amount` of the denom.
+    pub fn checked_sub_coin(&mut self, coin: &Coin) -> StdResult<()> {
+        if coin.amount.is_zero() {
+            return Ok(());
+        }
+        match self.amount_of(&coin.denom).checked_sub(coin.amount)? {
+            remaining if remaining.is_zero() => self.0.remove(&coin.denom),
+            remaining => self.0.insert(coin.denom.clone(), remaining),
+        };
+        Ok(())
+    }
+
+    /// Add all coins of `other` to the set. The set is left unchanged if an
+    /// error is returned.
+    pub fn checked_add(&mut self, other: &NativeCoins) -> StdResult<()> {
+        let mut sum = self.clone();
+        for coin in other.iter() {
+            sum.checked_add_coin(&coin)?;
+        }
+        *self = sum;
+        Ok(())
+    }
+
+    /// Subtract all coins of `other` from the set. The set is left unchanged
+    /// if an error is returned.
+    pub fn checked_sub(&mut self, other: &NativeCoins) -> StdResult<()> {
+        let mut difference = self.clone();
+        for coin in other.iter() {
+            difference.checked_sub_coin(&coin)?;
+        }
+        *self = difference;
+        Ok(())
+    }
+}
+
+impl fmt::Display for NativeCoins {
+    /// Formats the coins in the canonical Cosmos SDK form, e.g.
+    /// `100uatom,25uosmo`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", coins_to_string(&self.to_vec()))
+    }
+}
+
+impl TryFrom<&[Coin]> for NativeCoins {
+    type Error = StdError;
+
+    /// Merges coins with the same denom and drops zero amounts.
+    ///
+    /// ### Errors
+    /// Returns an error if any denom is invalid or an amount overflows.
+    fn try_from(coins: &[Coin]) -> StdResult<Self> {
+        Self::try_from_iter(coins.iter().cloned())
+    }
+}
+
+impl TryFrom<Vec<Coin>> for NativeCoins {
+    type Error = StdError;
+
+    fn try_from(coins: Vec<Coin>) -> StdResult<Self> {
+        Self::try_from(coins.as_slice())
+    }
+}
+
+impl TryFromIterator<Coin> for NativeCoins {
+    /// Merges coins with the same denom and drops zero amounts.
+    ///
+    /// ### Errors
+    /// Returns an error if any denom is invalid or an amount overflows.
+    fn try_from_iter<I: IntoIterator<Item = Coin>>(iter: I) -> StdResult<Self> {
+        let mut native_coins = Self::new();
+        for coin in iter {
+            native_coins.checked_add_coin(&coin)?;
+        }
+        Ok(native_coins)
+    }
+}
+
+impl From<NativeCoins> for Vec<Coin> {
+    fn from(coins: NativeCoins) -> Self {
+        coins
+            .0
+            .into_iter()
+            .map(|(denom, amount)| Coin { denom, amount })
+            .collect()
+    }
+}
+
+impl TryFrom<&AssetList> for NativeCoins {
+    type Error = StdError;
+
+    /// ### Errors
+    /// Returns an error if the list contains a CW20 token.
+    fn try_from(assets: &AssetList) -> StdResult<Self> {
+        let mut native_coins = Self::new();
+        for asset in assets {
+            native_coins.checked_add_coin(&asset.try_into()?)?;
+        }
+        Ok(native_coins)
+    }
+}
+
+impl From<NativeCoins> for AssetList {
+    fn from(coins: NativeCoins) -> Self {
+        coins
+            .0
+            .into_iter()
+            .map(|(denom, amount)| Asset::new(AssetInfo::Native(denom), amount))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{Addr, OverflowError, OverflowOperation};
+    use test_case::test_case;
+
+    #[test_case(vec![] => Ok(vec![]); "empty")]
+    #[test_case(vec![Coin::new(2, "uosmo"), Coin::new(1, "uatom")] => Ok(vec![Coin::new(1, "uatom"), Coin::new(2, "uosmo")]); "sorts by denom")]
+    #[test_case(vec![Coin::new(2, "uosmo"), Coin::new(3, "uosmo")] => Ok(vec![Coin::new(5, "uosmo")]); "merges duplicates")]
+    #[test_case(vec![Coin::new(0, "uosmo"), Coin::new(1, "uatom")] => Ok(vec![Coin::new(1, "uatom")]); "drops zero amounts")]
+    #[test_case(vec![Coin::new(1, "u")] => Err(StdError::generic_err("Provided string is not a valid CosmosSDK denom.")); "invalid denom")]
+    #[test_case(vec![Coin::new(u128::MAX, "uosmo"), Coin::new(1, "uosmo")] => Err(StdError::overflow(OverflowError::new(OverflowOperation::Add, u128::MAX, 1u128))); "overflow")]
+    fn test_try_from_vec(coins: Vec<Coin>) -> StdResult<Vec<Coin>> {
+        NativeCoins::try_from(coins).map(Into::into)
+    }
+
+    #[test_case(vec![Coin::new(5, "uosmo")], vec![Coin::new(2, "uosmo")] => Ok(vec![Coin::new(3, "uosmo")]); "partial")]
+    #[test_case(vec![Coin::new(5, "uosmo"), Coin::new(1, "uatom")], vec![Coin::new(5, "uosmo")] => Ok(vec![Coin::new(1, "uatom")]); "removes denom")]
+    #[test_case(vec![Coin::new(5, "uosmo")], vec![Coin::new(6, "uosmo")] => Err(StdError::overflow(OverflowError::new(OverflowOperation::Sub, 5u128, 6u128))); "insufficient amount")]
+    #[test_case(vec![Coin::new(5, "uosmo")], vec![Coin::new(1, "uatom")] => Err(StdError::overflow(OverflowError::new(OverflowOperation::Sub, 0u128, 1u128))); "missing denom")]
+    fn test_checked_sub(coins: Vec<Coin>, other: Vec<Coin>) -> StdResult<Vec<Coin>> {
+        let mut coins = NativeCoins::try_from(coins).unwrap();
+        coins.checked_sub(&NativeCoins::try_from(other).unwrap())?;
+        Ok(coins.into())
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let mut coins = NativeCoins::try_from(vec![Coin::new(1, "uosmo")]).unwrap();
+        coins
+            .checked_add(
+                &NativeCoins::try_from(vec![Coin::new(2, "uosmo"), Coin::new(3, "uatom")]).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(coins.amount_of("uosmo"), Uint128::new(3));
+        assert_eq!(coins.amount_of("uatom"), Uint128::new(3));
+        assert_eq!(coins.amount_of("uion"), Uint128::zero());
+        assert_eq!(coins.to_string(), "3uatom,3uosmo");
+    }
+
+    #[test]
+    fn test_failed_checked_sub_leaves_set_unchanged() {
+        let mut coins =
+            NativeCoins::try_from(vec![Coin::new(5, "uatom"), Coin::new(5, "uosmo")]).unwrap();
+        let before = coins.clone();
+
+        coins
+            .checked_sub(
+                &NativeCoins::try_from(vec![Coin::new(1, "uatom"), Coin::new(6, "uosmo")]).unwrap(),
+            )
+            .unwrap_err();
+
+        assert_eq!(coins, before);
+    }
+
+    #[test]
+    fn test_asset_list_conversion() {
+        let coins =
+            NativeCoins::try_from(vec![Coin::new(1, "uosmo"), Coin::new(2, "uatom")]).unwrap();
+        let assets = AssetList::from(coins.clone());
+
+        assert_eq!(
+            assets,
+            AssetList::from(vec![Coin::new(2, "uatom"), Coin::new(1, "uosmo")])
+        );
+        assert_eq!(NativeCoins::try_from(&assets).unwrap(), coins);
+
+        let mut assets = assets;
+        assets
+            .add(&Asset::cw20(Addr::unchecked("apollo"), 1u128))
+            .unwrap();
+        assert!(NativeCoins::try_from(&assets).is_err());
+    }
+}
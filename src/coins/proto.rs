@@ -0,0 +1,275 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Coin, Decimal, StdError, StdResult, Uint128};
+
+use super::DecCoin;
+
+const WIRE_TYPE_VARINT: u64 = 0;
+const WIRE_TYPE_FIXED64: u64 = 1;
+const WIRE_TYPE_LEN: u64 = 2;
+const WIRE_TYPE_FIXED32: u64 = 5;
+
+/// Encode a `Coin` as a protobuf `cosmos.base.v1beta1.Coin`, which has the
+/// denom as field 1 and the amount as a string in field 2.
+pub fn encode_coin(coin: &Coin) -> Vec<u8> {
+    encode_denom_and_amount(&coin.denom, &coin.amount.to_string())
+}
+
+/// Decode a protobuf `cosmos.base.v1beta1.Coin`.
+///
+/// ### Errors
+/// Returns an error if the bytes are not valid protobuf, or if the amount is
+/// not a valid `Uint128`.
+pub fn decode_coin(bytes: &[u8]) -> StdResult<Coin> {
+    let (denom, amount) = decode_denom_and_amount(bytes, "Coin")?;
+    let amount = decode_amount(&amount, "Coin")?;
+    Ok(Coin { denom, amount })
+}
+
+/// Encode the coins as the repeated `cosmos.base.v1beta1.Coin` field
+/// `field_number` of a protobuf message, e.g. field 3 for the amount of a
+/// `MsgSend`.
+pub fn encode_coins(field_number: u32, coins: &[Coin]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for coin in coins {
+        encode_message_field(&mut buf, field_number, &encode_coin(coin));
+    }
+    buf
+}
+
+/// Decode all coins in the repeated field `field_number` of a protobuf
+/// message, skipping all other fields.
+///
+/// ### Errors
+/// Returns an error if the bytes are not valid protobuf, or if any of the
+/// coins cannot be decoded with [`decode_coin`].
+pub fn decode_coins(field_number: u32, bytes: &[u8]) -> StdResult<Vec<Coin>> {
+    decode_fields(bytes, "Coin list")?
+        .into_iter()
+        .filter(|(number, _)| *number == field_number)
+        .map(|(_, value)| match value {
+            FieldValue::Len(value) => decode_coin(value),
+            FieldValue::Other => Err(proto_err("Coin list", "coin field is not length-delimited")),
+        })
+        .collect()
+}
+
+/// Encode a `DecCoin` as a protobuf `cosmos.base.v1beta1.DecCoin`. As in the
+/// Cosmos SDK, the amount is encoded as the integer string of its atomics,
+/// e.g. `1500000000000000000` for `1.5`.
+pub fn encode_dec_coin(coin: &DecCoin) -> Vec<u8> {
+    encode_denom_and_amount(&coin.denom, &coin.amount.atomics().to_string())
+}
+
+/// Decode a protobuf `cosmos.base.v1beta1.DecCoin`.
+///
+/// ### Errors
+/// Returns an error if the bytes are not valid protobuf, or if the amount is
+/// not a valid `Decimal`.
+pub fn decode_dec_coin(bytes: &[u8]) -> StdResult<DecCoin> {
+    let (denom, amount) = decode_denom_and_amount(bytes, "DecCoin")?;
+    let atomics = decode_amount(&amount, "DecCoin")?;
+    Ok(DecCoin::new(Decimal::new(atomics), denom))
+}
+
+/// Parse an integer amount string. An absent amount decodes as zero.
+fn decode_amount(amount: &str, type_name: &str) -> StdResult<Uint128> {
+    if amount.is_empty() {
+        return Ok(Uint128::zero());
+    }
+    Uint128::from_str(amount)
+        .map_err(|_| proto_err(type_name, &format!("invalid amount {:?}", amount)))
+}
+
+fn encode_denom_and_amount(denom: &str, amount: &str) -> Vec<u8> {
+    let mut buf = Vec::new();
+    encode_string_field(&mut buf, 1, denom);
+    encode_string_field(&mut buf, 2, amount);
+    buf
+}
+
+fn decode_denom_and_amount(bytes: &[u8], type_name: &str) -> StdResult<(String, String)> {
+    let mut denom = String::new();
+    let mut amount = String::new();
+    for (field_number, value) in decode_fields(bytes, type_name)? {
+        let target = match field_number {
+            1 => &mut denom,
+            2 => &mut amount,
+            _ => continue,
+        };
+        match value {
+            FieldValue::Len(value) => {
+                *target = String::from_utf8(value.to_vec())
+                    .map_err(|_| proto_err(type_name, "string field is not valid UTF-8"))?;
+            }
+            FieldValue::Other => {
+                return Err(proto_err(type_name, "string field is not length-delimited"))
+            }
+        }
+    }
+    Ok((denom, amount))
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn encode_key(buf: &mut Vec<u8>, field_number: u32, wire_type: u64) {
+    encode_varint(buf, u64::from(field_number) << 3 | wire_type);
+}
+
+/// Encode a string field. Empty strings are omitted, as proto3 does for
+/// default values.
+pub(crate) fn encode_string_field(buf: &mut Vec<u8>, field_number: u32, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    encode_key(buf, field_number, WIRE_TYPE_LEN);
+    encode_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// Encode an embedded message field. Unlike strings, the field is written
+/// even if the message is empty, as gogoproto does for non-nullable fields.
+pub(crate) fn encode_message_field(buf: &mut Vec<u8>, field_number: u32, message: &[u8]) {
+    encode_key(buf, field_number, WIRE_TYPE_LEN);
+    encode_varint(buf, message.len() as u64);
+    buf.extend_from_slice(message);
+}
+
+enum FieldValue<'a> {
+    Len(&'a [u8]),
+    /// A varint or fixed-size value, which no field decoded here uses.
+    Other,
+}
+
+/// Split a protobuf message into its fields, in order.
+fn decode_fields<'a>(
+    mut bytes: &'a [u8],
+    type_name: &str,
+) -> StdResult<Vec<(u32, FieldValue<'a>)>> {
+    let mut fields = Vec::new();
+    while !bytes.is_empty() {
+        let key = decode_varint(&mut bytes, type_name)?;
+        let field_number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|number| *number != 0)
+            .ok_or_else(|| proto_err(type_name, "invalid field number"))?;
+        let value = match key & 0x7 {
+            WIRE_TYPE_VARINT => {
+                decode_varint(&mut bytes, type_name)?;
+                FieldValue::Other
+            }
+            WIRE_TYPE_FIXED64 => {
+                take(&mut bytes, 8, type_name)?;
+                FieldValue::Other
+            }
+            WIRE_TYPE_LEN => {
+                let len = decode_varint(&mut bytes, type_name)?;
+                let len =
+                    usize::try_from(len).map_err(|_| proto_err(type_name, "field too long"))?;
+                FieldValue::Len(take(&mut bytes, len, type_name)?)
+            }
+            WIRE_TYPE_FIXED32 => {
+                take(&mut bytes, 4, type_name)?;
+                FieldValue::Other
+            }
+            _ => return Err(proto_err(type_name, "unsupported wire type")),
+        };
+        fields.push((field_number, value));
+    }
+    Ok(fields)
+}
+
+fn decode_varint(bytes: &mut &[u8], type_name: &str) -> StdResult<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(proto_err(type_name, "invalid varint"))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize, type_name: &str) -> StdResult<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(proto_err(type_name, "unexpected end of input"));
+    }
+    let (value, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(value)
+}
+
+fn proto_err(type_name: &str, msg: &str) -> StdError {
+    StdError::parse_err(type_name, format!("invalid protobuf encoding: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    // Golden vectors match the encoding produced by the Cosmos SDK
+    #[test_case(Coin::new(1000, "uatom") => b"\x0a\x05uatom\x12\x041000".to_vec(); "coin")]
+    #[test_case(Coin::new(0, "uatom") => b"\x0a\x05uatom\x12\x010".to_vec(); "zero amount is encoded")]
+    #[test_case(Coin::new(u128::MAX, "uosmo") => b"\x0a\x05uosmo\x12\x27340282366920938463463374607431768211455".to_vec(); "u128 max")]
+    #[test_case(Coin::new(1, "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2") => b"\x0a\x44ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2\x12\x011".to_vec(); "ibc denom")]
+    fn test_encode_coin(coin: Coin) -> Vec<u8> {
+        let encoded = encode_coin(&coin);
+        assert_eq!(decode_coin(&encoded), Ok(coin));
+        encoded
+    }
+
+    #[test_case(b"\x0a\x05uatom\x12\x041000" => Ok(Coin::new(1000, "uatom")); "coin")]
+    #[test_case(b"\x12\x041000\x0a\x05uatom" => Ok(Coin::new(1000, "uatom")); "fields out of order")]
+    #[test_case(b"\x0a\x05uatom" => Ok(Coin::new(0, "uatom")); "missing amount")]
+    #[test_case(b"\x0a\x05uatom\x12\x041000\x18\x01" => Ok(Coin::new(1000, "uatom")); "unknown field")]
+    #[test_case(b"\x0a\x05uatom\x12\x02-1" => Err(StdError::parse_err("Coin", "invalid protobuf encoding: invalid amount \"-1\"")); "negative amount")]
+    #[test_case(b"\x0a\x06uatom" => Err(StdError::parse_err("Coin", "invalid protobuf encoding: unexpected end of input")); "truncated")]
+    #[test_case(b"\x0a\x02\xff\xfe" => Err(StdError::parse_err("Coin", "invalid protobuf encoding: string field is not valid UTF-8")); "invalid utf8")]
+    #[test_case(b"\x08\x01" => Err(StdError::parse_err("Coin", "invalid protobuf encoding: string field is not length-delimited")); "wrong wire type")]
+    #[test_case(b"\x0a\x80" => Err(StdError::parse_err("Coin", "invalid protobuf encoding: invalid varint")); "invalid varint")]
+    fn test_decode_coin(bytes: &[u8]) -> StdResult<Coin> {
+        decode_coin(bytes)
+    }
+
+    #[test]
+    fn test_encode_coins() {
+        let coins = vec![Coin::new(1000, "uatom"), Coin::new(5, "uosmo")];
+        let encoded = encode_coins(3, &coins);
+
+        assert_eq!(
+            encoded,
+            b"\x1a\x0d\x0a\x05uatom\x12\x041000\x1a\x0a\x0a\x05uosmo\x12\x015".to_vec()
+        );
+        assert_eq!(decode_coins(3, &encoded), Ok(coins));
+        assert_eq!(decode_coins(2, &encoded), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_decode_coins_from_msg_send() {
+        // A MsgSend from "a" to "b" of 1000uatom
+        let msg_send = b"\x0a\x01a\x12\x01b\x1a\x0d\x0a\x05uatom\x12\x041000";
+
+        assert_eq!(
+            decode_coins(3, msg_send),
+            Ok(vec![Coin::new(1000, "uatom")])
+        );
+    }
+
+    #[test_case("1.5" => b"\x0a\x05uatom\x12\x131500000000000000000".to_vec(); "decimal")]
+    #[test_case("0" => b"\x0a\x05uatom\x12\x010".to_vec(); "zero")]
+    #[test_case("0.000000000000000001" => b"\x0a\x05uatom\x12\x011".to_vec(); "smallest amount")]
+    fn test_encode_dec_coin(amount: &str) -> Vec<u8> {
+        let coin = DecCoin::new(Decimal::from_str(amount).unwrap(), "uatom");
+        let encoded = encode_dec_coin(&coin);
+        assert_eq!(decode_dec_coin(&encoded), Ok(coin));
+        encoded
+    }
+}
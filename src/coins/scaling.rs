@@ -0,0 +1,147 @@
+use cosmwasm_std::{StdError, Uint128, Uint256};
+use thiserror::Error;
+
+use super::{pow10, Rounding};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ScaleError {
+    #[error("Rescaling {amount} from {from_decimals} to {to_decimals} decimals overflows")]
+    Overflow {
+        amount: Uint128,
+        from_decimals: u32,
+        to_decimals: u32,
+    },
+
+    #[error("Rescaling {amount} from {from_decimals} to {to_decimals} decimals loses precision")]
+    PrecisionLoss {
+        amount: Uint128,
+        from_decimals: u32,
+        to_decimals: u32,
+    },
+}
+
+impl From<ScaleError> for StdError {
+    fn from(err: ScaleError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+/// Rescale an amount of a token with `from_decimals` decimals to the same
+/// value in a token with `to_decimals` decimals, e.g. `1_000_000` with 6
+/// decimals to `100_000_000` with 8 decimals. When scaling down, the result is
+/// rounded with `rounding`.
+///
+/// ### Errors
+/// Returns [`ScaleError::Overflow`] if the result does not fit in a `Uint128`.
+pub fn rescale_amount(
+    amount: Uint128,
+    from_decimals: u32,
+    to_decimals: u32,
+    rounding: Rounding,
+) -> Result<Uint128, ScaleError> {
+    let overflow = || ScaleError::Overflow {
+        amount,
+        from_decimals,
+        to_decimals,
+    };
+
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+    if to_decimals >= from_decimals {
+        let factor = pow10::<Uint256>(to_decimals - from_decimals).ok_or_else(overflow)?;
+        let scaled = Uint256::from(amount)
+            .checked_mul(factor)
+            .map_err(|_| overflow())?;
+        return Uint128::try_from(scaled).map_err(|_| overflow());
+    }
+
+    let quotient = match pow10::<Uint256>(from_decimals - to_decimals) {
+        Some(divisor) => {
+            let amount = Uint256::from(amount);
+            let remainder = amount % divisor;
+            let round_up = match rounding {
+                Rounding::Floor => false,
+                Rounding::Ceil => !remainder.is_zero(),
+                Rounding::Round => {
+                    let doubled = remainder * Uint256::from(2u8);
+                    let quotient_is_odd = !((amount / divisor) % Uint256::from(2u8)).is_zero();
+                    doubled > divisor || (doubled == divisor && quotient_is_odd)
+                }
+            };
+            amount / divisor + Uint256::from(u8::from(round_up))
+        }
+        // 10^(from_decimals - to_decimals) is larger than any Uint128, so the
+        // whole amount is less than half of the smallest unit.
+        None => Uint256::from(u8::from(rounding == Rounding::Ceil)),
+    };
+    // The quotient is smaller than the amount, so it always fits
+    Uint128::try_from(quotient).map_err(|_| overflow())
+}
+
+/// Same as [`rescale_amount`], but requires the value to be represented
+/// exactly in `to_decimals` decimals.
+///
+/// ### Errors
+/// Returns [`ScaleError::PrecisionLoss`] if scaling down would drop non-zero
+/// digits.
+/// Returns [`ScaleError::Overflow`] if the result does not fit in a `Uint128`.
+pub fn rescale_amount_exact(
+    amount: Uint128,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<Uint128, ScaleError> {
+    let scaled = rescale_amount(amount, from_decimals, to_decimals, Rounding::Floor)?;
+    if rescale_amount(scaled, to_decimals, from_decimals, Rounding::Floor) != Ok(amount) {
+        return Err(ScaleError::PrecisionLoss {
+            amount,
+            from_decimals,
+            to_decimals,
+        });
+    }
+    Ok(scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1_000_000, 6, 8, Rounding::Floor => Ok(Uint128::new(100_000_000)); "scale up")]
+    #[test_case(1_000_000, 6, 6, Rounding::Floor => Ok(Uint128::new(1_000_000)); "same decimals")]
+    #[test_case(1_234_567, 8, 6, Rounding::Floor => Ok(Uint128::new(12_345)); "scale down floor")]
+    #[test_case(1_234_567, 8, 6, Rounding::Ceil => Ok(Uint128::new(12_346)); "scale down ceil")]
+    #[test_case(1_234_567, 8, 6, Rounding::Round => Ok(Uint128::new(12_346)); "scale down round up")]
+    #[test_case(1_234_549, 8, 6, Rounding::Round => Ok(Uint128::new(12_345)); "scale down round down")]
+    #[test_case(1_234_550, 8, 6, Rounding::Round => Ok(Uint128::new(12_346)); "scale down round half to even up")]
+    #[test_case(1_234_450, 8, 6, Rounding::Round => Ok(Uint128::new(12_344)); "scale down round half to even down")]
+    #[test_case(1, 18, 6, Rounding::Ceil => Ok(Uint128::new(1)); "dust ceil")]
+    #[test_case(1, 18, 6, Rounding::Round => Ok(Uint128::zero()); "dust round")]
+    #[test_case(u128::MAX, 100, 0, Rounding::Ceil => Ok(Uint128::one()); "divisor larger than Uint256 ceil")]
+    #[test_case(u128::MAX, 100, 0, Rounding::Round => Ok(Uint128::zero()); "divisor larger than Uint256 round")]
+    #[test_case(u128::MAX, 0, 0, Rounding::Ceil => Ok(Uint128::MAX); "max")]
+    #[test_case(u128::MAX, 18, 0, Rounding::Ceil => Ok(Uint128::new(340_282_366_920_938_463_464)); "max scale down")]
+    #[test_case(u128::MAX, 0, 1, Rounding::Floor => Err(ScaleError::Overflow { amount: Uint128::MAX, from_decimals: 0, to_decimals: 1 }); "overflow")]
+    #[test_case(1, 0, 100, Rounding::Floor => Err(ScaleError::Overflow { amount: Uint128::one(), from_decimals: 0, to_decimals: 100 }); "factor larger than Uint256")]
+    #[test_case(0, 0, 100, Rounding::Floor => Ok(Uint128::zero()); "zero")]
+    fn test_rescale_amount(
+        amount: u128,
+        from_decimals: u32,
+        to_decimals: u32,
+        rounding: Rounding,
+    ) -> Result<Uint128, ScaleError> {
+        rescale_amount(Uint128::new(amount), from_decimals, to_decimals, rounding)
+    }
+
+    #[test_case(1_234_500, 8, 6 => Ok(Uint128::new(12_345)); "exact")]
+    #[test_case(1_000_000, 6, 18 => Ok(Uint128::new(1_000_000_000_000_000_000)); "scale up")]
+    #[test_case(1_234_567, 8, 6 => Err(ScaleError::PrecisionLoss { amount: Uint128::new(1_234_567), from_decimals: 8, to_decimals: 6 }); "precision loss")]
+    #[test_case(u128::MAX, 0, 1 => Err(ScaleError::Overflow { amount: Uint128::MAX, from_decimals: 0, to_decimals: 1 }); "overflow")]
+    fn test_rescale_amount_exact(
+        amount: u128,
+        from_decimals: u32,
+        to_decimals: u32,
+    ) -> Result<Uint128, ScaleError> {
+        rescale_amount_exact(Uint128::new(amount), from_decimals, to_decimals)
+    }
+}
@@ -0,0 +1,438 @@
+use apollo_cw_asset::{AssetInfo, AssetInfoUnchecked};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Api, Coin, Order, StdError, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+
+use super::{validate_denom, DenomMetadata, DenomRegistry};
+use crate::pagination::page_limit;
+
+/// A [`DenomRegistry`] stored in contract state, keyed by base denom.
+pub struct DenomMetadataStore<'a> {
+    metadata: Map<'a, &'a str, DenomMetadata>,
+    bases: Map<'a, &'a str, String>,
+}
+
+impl<'a> DenomMetadataStore<'a> {
+    /// Create a new `DenomMetadataStore`, storing the metadata by base denom in
+    /// `metadata_namespace` and the display denom to base denom mapping in
+    /// `bases_namespace`.
+    pub const fn new(metadata_namespace: &'a str, bases_namespace: &'a str) -> Self {
+        Self {
+            metadata: Map::new(metadata_namespace),
+            bases: Map::new(bases_namespace),
+        }
+    }
+
+    /// Validate and save the metadata, replacing any existing metadata for
+    /// the same base denom.
+    ///
+    /// ### Errors
+    /// Returns an error if the metadata is invalid, or if the display denom is
+    /// already used by another base denom.
+    pub fn save(&self, storage: &mut dyn Storage, metadata: &DenomMetadata) -> StdResult<()> {
+        metadata.validate()?;
+        if let Some(base) = self.bases.may_load(storage, &metadata.display)? {
+            if base != metadata.base {
+                return Err(StdError::generic_err(format!(
+                    "Display denom {} is already used by {}",
+                    metadata.display, base
+                )));
+            }
+        }
+        self.remove(storage, &metadata.base)?;
+        self.bases
+            .save(storage, &metadata.display, &metadata.base)?;
+        self.metadata.save(storage, &metadata.base, metadata)
+    }
+
+    /// Removes the metadata of a base denom.
+    pub fn remove(&self, storage: &mut dyn Storage, base: &str) -> StdResult<()> {
+        if let Some(metadata) = self.metadata.may_load(storage, base)? {
+            self.bases.remove(storage, &metadata.display);
+            self.metadata.remove(storage, base);
+        }
+        Ok(())
+    }
+
+    /// Returns the metadata of a base denom, if any.
+    pub fn may_load(&self, storage: &dyn Storage, base: &str) -> StdResult<Option<DenomMetadata>> {
+        self.metadata.may_load(storage, base)
+    }
+
+    /// Returns the metadata with the given display denom, if any.
+    pub fn may_load_by_display(
+        &self,
+        storage: &dyn Storage,
+        display: &str,
+    ) -> StdResult<Option<DenomMetadata>> {
+        match self.bases.may_load(storage, display)? {
+            Some(base) => self.may_load(storage, &base),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads all stored metadata into a [`DenomRegistry`].
+    pub fn load_registry(&self, storage: &dyn Storage) -> StdResult<DenomRegistry> {
+        self.metadata
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, metadata)| metadata))
+            .collect::<StdResult<Vec<_>>>()?
+            .try_into()
+    }
+
+    /// Format a coin in its display denom, e.g. `1.5 ATOM`. Coins without
+    /// stored metadata are formatted as `{amount}{denom}`.
+    pub fn format_coin(&self, storage: &dyn Storage, coin: &Coin) -> StdResult<String> {
+        match self.may_load(storage, &coin.denom)? {
+            Some(metadata) => metadata.format_amount(coin.amount),
+            None => Ok(coin.to_string()),
+        }
+    }
+
+    /// Parse an amount in a stored display denom, e.g. `1.5 ATOM`, into a
+    /// `Coin` of the base denom.
+    pub fn parse_coin(&self, storage: &dyn Storage, s: &str) -> StdResult<Coin> {
+        let display = s.split_whitespace().last().unwrap_or_default();
+        self.may_load_by_display(storage, display)?
+            .ok_or_else(|| StdError::generic_err(format!("Unknown display denom {:?}", display)))?
+            .parse_amount(s)
+    }
+
+    /// Returns the underlying `Map` of metadata by base denom.
+    pub fn map(&self) -> &Map<'a, &'a str, DenomMetadata> {
+        &self.metadata
+    }
+}
+
+/// An update to a [`DenomAliasStore`], e.g. sent by an admin in an execute
+/// message.
+#[cw_serde]
+pub enum DenomAliasUpdate {
+    /// Set `alias` to refer to `denom`, replacing any previous alias of
+    /// `denom` and any previous denom of `alias`.
+    Set { alias: String, denom: String },
+    /// Remove `alias`.
+    Remove { alias: String },
+}
+
+/// A one-to-one mapping between aliases such as `ATOM` and native denoms such
+/// as `ibc/27394F...`, stored in contract state, so that messages can accept
+/// either form.
+pub struct DenomAliasStore<'a> {
+    denoms: Map<'a, &'a str, String>,
+    aliases: Map<'a, &'a str, String>,
+}
+
+impl<'a> DenomAliasStore<'a> {
+    /// Create a new `DenomAliasStore`, storing the alias to denom mapping in
+    /// `denoms_namespace` and the reverse mapping in `aliases_namespace`.
+    pub const fn new(denoms_namespace: &'a str, aliases_namespace: &'a str) -> Self {
+        Self {
+            denoms: Map::new(denoms_namespace),
+            aliases: Map::new(aliases_namespace),
+        }
+    }
+
+    /// Apply the updates in order.
+    ///
+    /// ### Errors
+    /// Returns an error if an alias is empty, contains whitespace or is longer
+    /// than 128 characters, or if a denom is not valid according to
+    /// [`validate_denom`].
+    pub fn update(&self, storage: &mut dyn Storage, updates: &[DenomAliasUpdate]) -> StdResult<()> {
+        for update in updates {
+            match update {
+                DenomAliasUpdate::Set { alias, denom } => {
+                    validate_alias(alias)?;
+                    validate_denom(denom)?;
+                    self.remove(storage, alias)?;
+                    if let Some(old_alias) = self.aliases.may_load(storage, denom)? {
+                        self.denoms.remove(storage, &old_alias);
+                    }
+                    self.denoms.save(storage, alias, denom)?;
+                    self.aliases.save(storage, denom, alias)?;
+                }
+                DenomAliasUpdate::Remove { alias } => self.remove(storage, alias)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the denom `alias_or_denom` refers to: the aliased denom if it is
+    /// a known alias, and otherwise the input itself if it is a valid denom.
+    /// Aliases take precedence over denoms with the same name.
+    ///
+    /// ### Errors
+    /// Returns an error if the input is neither a known alias nor a valid
+    /// denom.
+    pub fn resolve(&self, storage: &dyn Storage, alias_or_denom: &str) -> StdResult<String> {
+        match self.denoms.may_load(storage, alias_or_denom)? {
+            Some(denom) => Ok(denom),
+            None => {
+                validate_denom(alias_or_denom).map_err(|_| {
+                    StdError::generic_err(format!(
+                        "{} is neither a known denom alias nor a valid denom",
+                        alias_or_denom
+                    ))
+                })?;
+                Ok(alias_or_denom.to_string())
+            }
+        }
+    }
+
+    /// Returns the alias of `denom`, if it has one.
+    pub fn alias_of(&self, storage: &dyn Storage, denom: &str) -> StdResult<Option<String>> {
+        self.aliases.may_load(storage, denom)
+    }
+
+    /// Validate an `AssetInfoUnchecked`, resolving the denom of a native token
+    /// with [`resolve`](Self::resolve).
+    pub fn resolve_asset_info(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        info: &AssetInfoUnchecked,
+    ) -> StdResult<AssetInfo> {
+        match info {
+            AssetInfoUnchecked::Native(denom) => {
+                Ok(AssetInfo::Native(self.resolve(storage, denom)?))
+            }
+            AssetInfoUnchecked::Cw20(_) => info.check(api),
+        }
+    }
+
+    /// Returns the (alias, denom) pairs, paginated by alias.
+    pub fn query_aliases(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(String, String)>> {
+        let limit = page_limit(limit);
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        self.denoms
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect()
+    }
+
+    fn remove(&self, storage: &mut dyn Storage, alias: &str) -> StdResult<()> {
+        if let Some(denom) = self.denoms.may_load(storage, alias)? {
+            self.denoms.remove(storage, alias);
+            self.aliases.remove(storage, &denom);
+        }
+        Ok(())
+    }
+}
+
+fn validate_alias(alias: &str) -> StdResult<()> {
+    if alias.is_empty() || alias.len() > 128 || alias.contains(char::is_whitespace) {
+        return Err(StdError::generic_err(format!(
+            "Invalid denom alias {:?}",
+            alias
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{MockApi, MockStorage};
+    use cosmwasm_std::Addr;
+
+    const METADATA: DenomMetadataStore = DenomMetadataStore::new("metadata", "metadata_bases");
+
+    #[test]
+    fn test_denom_metadata_store() {
+        let mut storage = MockStorage::new();
+        METADATA
+            .save(&mut storage, &DenomMetadata::new("uatom", "ATOM", 6))
+            .unwrap();
+
+        assert_eq!(
+            METADATA
+                .format_coin(&storage, &Coin::new(1_500_000, "uatom"))
+                .unwrap(),
+            "1.5 ATOM"
+        );
+        assert_eq!(
+            METADATA
+                .format_coin(&storage, &Coin::new(1, "uosmo"))
+                .unwrap(),
+            "1uosmo"
+        );
+        assert_eq!(
+            METADATA.parse_coin(&storage, "2 ATOM").unwrap(),
+            Coin::new(2_000_000, "uatom")
+        );
+        assert_eq!(
+            METADATA
+                .load_registry(&storage)
+                .unwrap()
+                .parse_coin("2 ATOM")
+                .unwrap(),
+            Coin::new(2_000_000, "uatom")
+        );
+
+        assert_eq!(
+            METADATA
+                .save(&mut storage, &DenomMetadata::new("ibc/atom", "ATOM", 6))
+                .unwrap_err(),
+            StdError::generic_err("Display denom ATOM is already used by uatom")
+        );
+
+        METADATA.remove(&mut storage, "uatom").unwrap();
+        assert_eq!(METADATA.may_load(&storage, "uatom").unwrap(), None);
+        assert_eq!(
+            METADATA.may_load_by_display(&storage, "ATOM").unwrap(),
+            None
+        );
+        assert_eq!(
+            METADATA.parse_coin(&storage, "2 ATOM").unwrap_err(),
+            StdError::generic_err("Unknown display denom \"ATOM\"")
+        );
+    }
+
+    #[test]
+    fn test_denom_metadata_store_change_display() {
+        let mut storage = MockStorage::new();
+        METADATA
+            .save(&mut storage, &DenomMetadata::new("uatom", "ATOM", 6))
+            .unwrap();
+        METADATA
+            .save(&mut storage, &DenomMetadata::new("uatom", "MATOM", 3))
+            .unwrap();
+
+        // The old display denom is freed up for other base denoms
+        assert_eq!(
+            METADATA.may_load_by_display(&storage, "ATOM").unwrap(),
+            None
+        );
+        METADATA
+            .save(&mut storage, &DenomMetadata::new("ibc/atom", "ATOM", 6))
+            .unwrap();
+        assert_eq!(
+            METADATA.may_load_by_display(&storage, "MATOM").unwrap(),
+            Some(DenomMetadata::new("uatom", "MATOM", 3))
+        );
+        assert_eq!(
+            METADATA.may_load_by_display(&storage, "ATOM").unwrap(),
+            Some(DenomMetadata::new("ibc/atom", "ATOM", 6))
+        );
+    }
+
+    const ALIASES: DenomAliasStore = DenomAliasStore::new("alias_denoms", "denom_aliases");
+    const ATOM: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    fn set(alias: &str, denom: &str) -> DenomAliasUpdate {
+        DenomAliasUpdate::Set {
+            alias: alias.to_string(),
+            denom: denom.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_denom_alias_store_resolve() {
+        let mut storage = MockStorage::new();
+        ALIASES.update(&mut storage, &[set("ATOM", ATOM)]).unwrap();
+
+        assert_eq!(ALIASES.resolve(&storage, "ATOM").unwrap(), ATOM);
+        assert_eq!(ALIASES.resolve(&storage, ATOM).unwrap(), ATOM);
+        assert_eq!(ALIASES.resolve(&storage, "uosmo").unwrap(), "uosmo");
+        assert_eq!(
+            ALIASES.resolve(&storage, "u").unwrap_err(),
+            StdError::generic_err("u is neither a known denom alias nor a valid denom")
+        );
+        assert_eq!(
+            ALIASES.alias_of(&storage, ATOM).unwrap(),
+            Some("ATOM".to_string())
+        );
+        assert_eq!(ALIASES.alias_of(&storage, "uosmo").unwrap(), None);
+    }
+
+    #[test]
+    fn test_denom_alias_store_update() {
+        let mut storage = MockStorage::new();
+        ALIASES
+            .update(
+                &mut storage,
+                &[set("ATOM", ATOM), set("OSMO", "uosmo"), set("COSMOS", ATOM)],
+            )
+            .unwrap();
+
+        // Setting a new alias for a denom replaces the old alias
+        assert_eq!(
+            ALIASES.query_aliases(&storage, None, None).unwrap(),
+            vec![
+                ("COSMOS".to_string(), ATOM.to_string()),
+                ("OSMO".to_string(), "uosmo".to_string()),
+            ]
+        );
+
+        // Pointing an alias at another denom removes its old reverse mapping
+        ALIASES
+            .update(&mut storage, &[set("OSMO", "uion")])
+            .unwrap();
+        assert_eq!(ALIASES.alias_of(&storage, "uosmo").unwrap(), None);
+        assert_eq!(
+            ALIASES.alias_of(&storage, "uion").unwrap(),
+            Some("OSMO".to_string())
+        );
+
+        ALIASES
+            .update(
+                &mut storage,
+                &[DenomAliasUpdate::Remove {
+                    alias: "COSMOS".to_string(),
+                }],
+            )
+            .unwrap();
+        assert_eq!(ALIASES.alias_of(&storage, ATOM).unwrap(), None);
+        assert_eq!(
+            ALIASES
+                .query_aliases(&storage, Some("COSMOS".to_string()), Some(1))
+                .unwrap(),
+            vec![("OSMO".to_string(), "uion".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_denom_alias_store_invalid_updates() {
+        let mut storage = MockStorage::new();
+
+        assert_eq!(
+            ALIASES
+                .update(&mut storage, &[set("AT OM", ATOM)])
+                .unwrap_err(),
+            StdError::generic_err("Invalid denom alias \"AT OM\"")
+        );
+        assert_eq!(
+            ALIASES
+                .update(&mut storage, &[set("ATOM", "u")])
+                .unwrap_err(),
+            StdError::generic_err("Provided string is not a valid CosmosSDK denom.")
+        );
+    }
+
+    #[test]
+    fn test_denom_alias_store_resolve_asset_info() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        ALIASES.update(&mut storage, &[set("ATOM", ATOM)]).unwrap();
+
+        assert_eq!(
+            ALIASES
+                .resolve_asset_info(&storage, &api, &AssetInfoUnchecked::native("ATOM"))
+                .unwrap(),
+            AssetInfo::native(ATOM)
+        );
+        assert_eq!(
+            ALIASES
+                .resolve_asset_info(&storage, &api, &AssetInfoUnchecked::cw20("apollo"))
+                .unwrap(),
+            AssetInfo::cw20(Addr::unchecked("apollo"))
+        );
+    }
+}
@@ -1,7 +1,117 @@
+use std::fmt::{self, Debug, Display};
+
+use apollo_cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetList};
+use cosmwasm_std::{Addr, Api, Deps, StdError, StdResult};
+use cw20::{Cw20Coin, Cw20CoinVerified};
+
+use crate::assets::check_cw20_coin;
+
+/// The error of a failed conversion of one element in
+/// [`TryIntoElementwise`], together with the index of the element.
+#[derive(Debug, PartialEq)]
+pub struct ElementError<E> {
+    pub index: usize,
+    /// The `Debug` representation of the element, if requested.
+    pub element: Option<String>,
+    pub error: E,
+}
+
+impl<E: Display> Display for ElementError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Element {}", self.index)?;
+        if let Some(element) = &self.element {
+            write!(f, " ({})", element)?;
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl<E: Debug + Display> std::error::Error for ElementError<E> {}
+
+impl<E: Display> From<ElementError<E>> for StdError {
+    fn from(err: ElementError<E>) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+/// The errors of all failed conversions in
+/// [`TryIntoElementwise::try_into_elementwise_all`], in order of index.
+#[derive(Debug, PartialEq)]
+pub struct ElementErrors<E>(pub Vec<ElementError<E>>);
+
+impl<E: Display> Display for ElementErrors<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self.0.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", errors.join(", "))
+    }
+}
+
+impl<E: Debug + Display> std::error::Error for ElementErrors<E> {}
+
+impl<E: Display> From<ElementErrors<E>> for StdError {
+    fn from(err: ElementErrors<E>) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+/// A collection that can be built from an iterator, but where building it can
+/// fail, e.g. because merging two entries overflows. Used as the target of
+/// [`TryIntoElementwise::try_into_collect_fallible`] and
+/// [`IntoElementwise::into_collect_fallible`] for collections such as
+/// `AssetList` and [`NativeCoins`](crate::coins::NativeCoins) that cannot
+/// implement `FromIterator`.
+pub trait TryFromIterator<A>: Sized {
+    fn try_from_iter<I: IntoIterator<Item = A>>(iter: I) -> StdResult<Self>;
+}
+
+impl TryFromIterator<Asset> for AssetList {
+    /// Merges assets with the same info.
+    ///
+    /// ### Errors
+    /// Returns an error if an amount overflows.
+    fn try_from_iter<I: IntoIterator<Item = Asset>>(iter: I) -> StdResult<Self> {
+        let mut assets = AssetList::new();
+        for asset in iter {
+            assets.add(&asset)?;
+        }
+        Ok(assets)
+    }
+}
+
 pub trait TryIntoElementwise<A, B: TryInto<A, Error = E>, E>: IntoIterator {
     /// Performs try_into on each element of the iterator and collects the
     /// results into a Vec.
     fn try_into_elementwise(self) -> Result<Vec<A>, E>;
+
+    /// Performs try_into on each element of the iterator and collects the
+    /// results into any collection, e.g. a `BTreeSet` or `HashMap`.
+    fn try_into_collect<C: FromIterator<A>>(self) -> Result<C, E>;
+
+    /// Performs try_into on each element of the iterator and collects the
+    /// results into a collection that is fallible to build, e.g. an
+    /// `AssetList` or [`NativeCoins`](crate::coins::NativeCoins).
+    fn try_into_collect_fallible<C: TryFromIterator<A>>(self) -> StdResult<C>
+    where
+        StdError: From<E>;
+
+    /// Same as [`try_into_elementwise`](Self::try_into_elementwise), but
+    /// returns the index of the first element that failed together with its
+    /// error.
+    fn try_into_elementwise_indexed(self) -> Result<Vec<A>, ElementError<E>>;
+
+    /// Same as
+    /// [`try_into_elementwise_indexed`](Self::try_into_elementwise_indexed),
+    /// but also includes the `Debug` representation of the failed element in
+    /// the error. Each element is cloned before the conversion so that it can
+    /// be formatted if the conversion fails, but only the failed element is
+    /// formatted.
+    fn try_into_elementwise_debug(self) -> Result<Vec<A>, ElementError<E>>
+    where
+        B: Debug + Clone;
+
+    /// Performs try_into on every element, and returns either all results or
+    /// the errors of all elements that failed.
+    fn try_into_elementwise_all(self) -> Result<Vec<A>, ElementErrors<E>>;
 }
 
 impl<A, B, E, I> TryIntoElementwise<A, B, E> for I
@@ -10,9 +120,69 @@ where
     I: IntoIterator<Item = B>,
 {
     fn try_into_elementwise(self) -> Result<Vec<A>, E> {
+        self.try_into_collect()
+    }
+
+    fn try_into_collect<C: FromIterator<A>>(self) -> Result<C, E> {
+        self.into_iter().map(|x| x.try_into()).collect()
+    }
+
+    fn try_into_collect_fallible<C: TryFromIterator<A>>(self) -> StdResult<C>
+    where
+        StdError: From<E>,
+    {
+        let elements: Vec<A> = self.try_into_elementwise()?;
+        C::try_from_iter(elements)
+    }
+
+    fn try_into_elementwise_indexed(self) -> Result<Vec<A>, ElementError<E>> {
+        self.into_iter()
+            .enumerate()
+            .map(|(index, x)| {
+                x.try_into().map_err(|error| ElementError {
+                    index,
+                    element: None,
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    fn try_into_elementwise_debug(self) -> Result<Vec<A>, ElementError<E>>
+    where
+        B: Debug + Clone,
+    {
         self.into_iter()
-            .map(|x| x.try_into())
-            .collect::<Result<Vec<_>, E>>()
+            .enumerate()
+            .map(|(index, x)| {
+                x.clone().try_into().map_err(|error| ElementError {
+                    index,
+                    element: Some(format!("{:?}", x)),
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    fn try_into_elementwise_all(self) -> Result<Vec<A>, ElementErrors<E>> {
+        let mut results = vec![];
+        let mut errors = vec![];
+        for (index, x) in self.into_iter().enumerate() {
+            match x.try_into() {
+                Ok(result) => results.push(result),
+                Err(error) => errors.push(ElementError {
+                    index,
+                    element: None,
+                    error,
+                }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(results)
+        } else {
+            Err(ElementErrors(errors))
+        }
     }
 }
 
@@ -20,6 +190,15 @@ pub trait IntoElementwise<A, B: Into<A>>: IntoIterator {
     /// Performs into on each element of the iterator and collects the
     /// results into a Vec.
     fn into_elementwise(self) -> Vec<A>;
+
+    /// Performs into on each element of the iterator and collects the results
+    /// into any collection, e.g. a `BTreeSet` or `HashMap`.
+    fn into_collect<C: FromIterator<A>>(self) -> C;
+
+    /// Performs into on each element of the iterator and collects the results
+    /// into a collection that is fallible to build, e.g. an `AssetList` or
+    /// [`NativeCoins`](crate::coins::NativeCoins).
+    fn into_collect_fallible<C: TryFromIterator<A>>(self) -> StdResult<C>;
 }
 
 impl<A, B, I> IntoElementwise<A, B> for I
@@ -28,14 +207,165 @@ where
     I: IntoIterator<Item = B>,
 {
     fn into_elementwise(self) -> Vec<A> {
+        self.into_collect()
+    }
+
+    fn into_collect<C: FromIterator<A>>(self) -> C {
         self.into_iter().map(|x| x.into()).collect()
     }
+
+    fn into_collect_fallible<C: TryFromIterator<A>>(self) -> StdResult<C> {
+        C::try_from_iter(self.into_iter().map(|x| x.into()))
+    }
+}
+
+pub trait MapElementwise<K, V>: IntoIterator<Item = (K, V)> {
+    /// Converts the keys and values of a map, or any other iterator of pairs,
+    /// separately and collects the results into any collection, e.g. a
+    /// `BTreeMap`.
+    fn map_elementwise<K2, V2, C>(
+        self,
+        map_key: impl FnMut(K) -> K2,
+        map_value: impl FnMut(V) -> V2,
+    ) -> C
+    where
+        C: FromIterator<(K2, V2)>;
+
+    /// Same as [`map_elementwise`](Self::map_elementwise), but with fallible
+    /// conversions. Returns the first error, e.g. of validating the keys of a
+    /// `HashMap<String, Uint128>` with `Api::addr_validate`.
+    fn try_map_elementwise<K2, V2, E, C>(
+        self,
+        map_key: impl FnMut(K) -> Result<K2, E>,
+        map_value: impl FnMut(V) -> Result<V2, E>,
+    ) -> Result<C, E>
+    where
+        C: FromIterator<(K2, V2)>;
+}
+
+impl<K, V, I> MapElementwise<K, V> for I
+where
+    I: IntoIterator<Item = (K, V)>,
+{
+    fn map_elementwise<K2, V2, C>(
+        self,
+        mut map_key: impl FnMut(K) -> K2,
+        mut map_value: impl FnMut(V) -> V2,
+    ) -> C
+    where
+        C: FromIterator<(K2, V2)>,
+    {
+        self.into_iter()
+            .map(|(k, v)| (map_key(k), map_value(v)))
+            .collect()
+    }
+
+    fn try_map_elementwise<K2, V2, E, C>(
+        self,
+        mut map_key: impl FnMut(K) -> Result<K2, E>,
+        mut map_value: impl FnMut(V) -> Result<V2, E>,
+    ) -> Result<C, E>
+    where
+        C: FromIterator<(K2, V2)>,
+    {
+        self.into_iter()
+            .map(|(k, v)| Ok((map_key(k)?, map_value(v)?)))
+            .collect()
+    }
+}
+
+/// A conversion that needs a context, such as `&dyn Api` to validate
+/// addresses. Used by [`TryIntoElementwiseWith`].
+pub trait TryIntoWith<A, Ctx> {
+    type Error;
+
+    fn try_into_with(self, ctx: Ctx) -> Result<A, Self::Error>;
+}
+
+impl<'a> TryIntoWith<Addr, &'a dyn Api> for String {
+    type Error = StdError;
+
+    fn try_into_with(self, api: &'a dyn Api) -> StdResult<Addr> {
+        api.addr_validate(&self)
+    }
+}
+
+impl<'a> TryIntoWith<Addr, &'a dyn Api> for &str {
+    type Error = StdError;
+
+    fn try_into_with(self, api: &'a dyn Api) -> StdResult<Addr> {
+        api.addr_validate(self)
+    }
+}
+
+impl<'a> TryIntoWith<Cw20CoinVerified, &'a dyn Api> for Cw20Coin {
+    type Error = StdError;
+
+    fn try_into_with(self, api: &'a dyn Api) -> StdResult<Cw20CoinVerified> {
+        check_cw20_coin(api, &self)
+    }
+}
+
+impl<'a> TryIntoWith<AssetInfo, &'a dyn Api> for AssetInfoUnchecked {
+    type Error = StdError;
+
+    fn try_into_with(self, api: &'a dyn Api) -> StdResult<AssetInfo> {
+        self.check(api)
+    }
+}
+
+/// Every conversion with `&dyn Api` as context can also be done with `Deps`.
+impl<'a, A, T> TryIntoWith<A, Deps<'a>> for T
+where
+    T: TryIntoWith<A, &'a dyn Api>,
+{
+    type Error = T::Error;
+
+    fn try_into_with(self, deps: Deps<'a>) -> Result<A, Self::Error> {
+        self.try_into_with(deps.api)
+    }
+}
+
+pub trait TryIntoElementwiseWith<A, B: TryIntoWith<A, Ctx>, Ctx: Copy>: IntoIterator {
+    /// Performs try_into_with on each element of the iterator, passing the
+    /// same context to each conversion, and collects the results into a Vec.
+    /// E.g. validates a `Vec<String>` of addresses into a `Vec<Addr>` with
+    /// `deps.api` as context.
+    fn try_into_elementwise_with(self, ctx: Ctx) -> Result<Vec<A>, B::Error>;
+
+    /// Same as
+    /// [`try_into_elementwise_with`](Self::try_into_elementwise_with), but
+    /// collects the results into any collection.
+    fn try_into_collect_with<C: FromIterator<A>>(self, ctx: Ctx) -> Result<C, B::Error>;
+}
+
+impl<A, B, Ctx, I> TryIntoElementwiseWith<A, B, Ctx> for I
+where
+    B: TryIntoWith<A, Ctx>,
+    Ctx: Copy,
+    I: IntoIterator<Item = B>,
+{
+    fn try_into_elementwise_with(self, ctx: Ctx) -> Result<Vec<A>, B::Error> {
+        self.try_into_collect_with(ctx)
+    }
+
+    fn try_into_collect_with<C: FromIterator<A>>(self, ctx: Ctx) -> Result<C, B::Error> {
+        self.into_iter().map(|x| x.try_into_with(ctx)).collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TryIntoElementwise;
+    use super::{
+        ElementError, ElementErrors, MapElementwise, TryIntoElementwise, TryIntoElementwiseWith,
+    };
+    use crate::coins::NativeCoins;
     use crate::iterators::IntoElementwise;
+    use apollo_cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetList};
+    use cosmwasm_std::testing::{mock_dependencies, MockApi};
+    use cosmwasm_std::{Addr, Api, Coin, StdError, StdResult, Uint128};
+    use cw20::{Cw20Coin, Cw20CoinVerified};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
     use std::num::TryFromIntError;
     use test_case::test_case;
 
@@ -50,10 +380,298 @@ mod tests {
         input.into_iter().try_into_elementwise()
     }
 
+    fn int_error() -> TryFromIntError {
+        u32::try_from(u64::MAX).unwrap_err()
+    }
+
+    #[test_case(
+        vec![1u64, 2u64, 3u64] => Ok(vec![1u32, 2u32, 3u32]);
+        "Element wise OK")]
+    #[test_case(
+        vec![1u64, u64::MAX, u64::MAX] => Err(ElementError { index: 1, element: None, error: int_error() });
+        "Element wise Fail")]
+    fn test_try_into_elementwise_indexed(
+        input: Vec<u64>,
+    ) -> Result<Vec<u32>, ElementError<TryFromIntError>> {
+        input.try_into_elementwise_indexed()
+    }
+
+    #[test]
+    fn test_try_into_elementwise_debug() {
+        let err = vec![1u64, u64::MAX]
+            .try_into_elementwise_debug()
+            .map(|_: Vec<u32>| ())
+            .unwrap_err();
+
+        assert_eq!(
+            StdError::from(err),
+            StdError::generic_err(
+                "Element 1 (18446744073709551615): out of range integral type conversion attempted"
+            )
+        );
+    }
+
+    /// An element that panics when formatted, to check that successfully
+    /// converted elements are not formatted.
+    #[derive(Clone)]
+    struct NoDebug(u64);
+
+    impl std::fmt::Debug for NoDebug {
+        fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            panic!("successfully converted element was formatted")
+        }
+    }
+
+    impl From<NoDebug> for u64 {
+        fn from(x: NoDebug) -> Self {
+            x.0
+        }
+    }
+
+    #[test]
+    fn test_try_into_elementwise_debug_formats_only_failures() {
+        let res: Result<Vec<u64>, _> = vec![NoDebug(1), NoDebug(2)].try_into_elementwise_debug();
+        assert_eq!(res, Ok(vec![1, 2]));
+    }
+
+    #[test_case(
+        vec![1u64, 2u64, 3u64] => Ok(vec![1u32, 2u32, 3u32]);
+        "Element wise OK")]
+    #[test_case(
+        vec![u64::MAX, 2u64, u64::MAX] => Err(ElementErrors(vec![
+            ElementError { index: 0, element: None, error: int_error() },
+            ElementError { index: 2, element: None, error: int_error() },
+        ]));
+        "Element wise Fail")]
+    fn test_try_into_elementwise_all(
+        input: Vec<u64>,
+    ) -> Result<Vec<u32>, ElementErrors<TryFromIntError>> {
+        input.try_into_elementwise_all()
+    }
+
+    #[test]
+    fn test_element_errors_display() {
+        let errors = ElementErrors(vec![
+            ElementError {
+                index: 0,
+                element: None,
+                error: "invalid",
+            },
+            ElementError {
+                index: 2,
+                element: Some("\"x\"".to_string()),
+                error: "invalid",
+            },
+        ]);
+
+        assert_eq!(
+            errors.to_string(),
+            "Element 0: invalid, Element 2 (\"x\"): invalid"
+        );
+    }
+
     #[test_case(
         vec![1u32, 2u32, 3u32] => vec![1u64, 2u64, 3u64];
         "Element wise OK")]
     fn test_into_elementwise(input: Vec<u32>) -> Vec<u64> {
         input.into_elementwise()
     }
+
+    #[test]
+    fn test_try_into_collect() {
+        let set: BTreeSet<u32> = vec![3u64, 1u64, 3u64].try_into_collect().unwrap();
+        assert_eq!(set, BTreeSet::from([1, 3]));
+
+        let set: HashSet<u32> = vec![3u64, 1u64, 3u64].try_into_collect().unwrap();
+        assert_eq!(set, HashSet::from([1, 3]));
+
+        let err = vec![1u64, u64::MAX]
+            .try_into_collect::<BTreeSet<u32>>()
+            .unwrap_err();
+        assert_eq!(err, int_error());
+    }
+
+    #[test]
+    fn test_into_collect() {
+        let set: BTreeSet<u64> = vec![2u32, 1u32].into_collect();
+        assert_eq!(set, BTreeSet::from([1, 2]));
+
+        let set: HashSet<u64> = vec![2u32, 1u32, 2u32].into_collect();
+        assert_eq!(set, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn test_into_collect_native_coins() {
+        let coins: NativeCoins = vec![
+            Coin::new(1, "uosmo"),
+            Coin::new(2, "uatom"),
+            Coin::new(3, "uosmo"),
+        ]
+        .into_collect_fallible()
+        .unwrap();
+        assert_eq!(
+            coins.to_vec(),
+            vec![Coin::new(2, "uatom"), Coin::new(4, "uosmo")]
+        );
+
+        let err = vec![Coin::new(1, "u")]
+            .into_collect_fallible::<NativeCoins>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Provided string is not a valid CosmosSDK denom.")
+        );
+    }
+
+    #[test]
+    fn test_into_collect_asset_list() {
+        let assets: AssetList = vec![
+            Coin::new(1, "uosmo"),
+            Coin::new(2, "uatom"),
+            Coin::new(3, "uosmo"),
+        ]
+        .into_collect_fallible()
+        .unwrap();
+        assert_eq!(
+            assets,
+            AssetList::from(vec![
+                Asset::native("uosmo", 4u128),
+                Asset::native("uatom", 2u128),
+            ])
+        );
+
+        let res = vec![Coin::new(u128::MAX, "uosmo"), Coin::new(1, "uosmo")]
+            .into_collect_fallible::<AssetList>();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_try_into_collect_fallible() {
+        let api = MockApi::default();
+        let cw20s = vec![
+            Cw20Coin {
+                address: "apollo".to_string(),
+                amount: Uint128::new(1),
+            },
+            Cw20Coin {
+                address: "apollo".to_string(),
+                amount: Uint128::new(2),
+            },
+        ];
+        let verified: Vec<Cw20CoinVerified> =
+            cw20s.try_into_elementwise_with(&api as &dyn Api).unwrap();
+
+        let assets: AssetList = verified.into_collect_fallible().unwrap();
+        assert_eq!(
+            assets,
+            AssetList::from(vec![Asset::cw20(Addr::unchecked("apollo"), 3u128)])
+        );
+
+        let coins: NativeCoins = vec![Asset::native("uosmo", 1u128)]
+            .try_into_collect_fallible()
+            .unwrap();
+        assert_eq!(coins.to_vec(), vec![Coin::new(1, "uosmo")]);
+
+        // A CW20 asset cannot be converted into a Coin
+        let res = vec![Asset::cw20(Addr::unchecked("apollo"), 1u128)]
+            .try_into_collect_fallible::<NativeCoins>();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_map_elementwise() {
+        let map: BTreeMap<u64, String> = HashMap::from([(1u32, "a"), (2u32, "b")])
+            .map_elementwise(u64::from, |v: &str| v.to_uppercase());
+
+        assert_eq!(
+            map,
+            BTreeMap::from([(1u64, "A".to_string()), (2u64, "B".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_try_map_elementwise_validates_addresses() {
+        let api = MockApi::default();
+        let balances = HashMap::from([
+            ("bob".to_string(), Uint128::new(2)),
+            ("alice".to_string(), Uint128::new(1)),
+        ]);
+
+        let validated: BTreeMap<Addr, Uint128> = balances
+            .try_map_elementwise(|addr| api.addr_validate(&addr), Ok)
+            .unwrap();
+        assert_eq!(
+            validated,
+            BTreeMap::from([
+                (Addr::unchecked("alice"), Uint128::new(1)),
+                (Addr::unchecked("bob"), Uint128::new(2)),
+            ])
+        );
+
+        let invalid = HashMap::from([("".to_string(), Uint128::new(1))]);
+        let res: StdResult<BTreeMap<Addr, Uint128>> =
+            invalid.try_map_elementwise(|addr| api.addr_validate(&addr), Ok);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_try_into_elementwise_with_addresses() {
+        let deps = mock_dependencies();
+        let addrs = vec!["alice".to_string(), "bob".to_string()];
+
+        let validated: Vec<Addr> = addrs.try_into_elementwise_with(deps.as_ref().api).unwrap();
+        assert_eq!(
+            validated,
+            vec![Addr::unchecked("alice"), Addr::unchecked("bob")]
+        );
+
+        let set: BTreeSet<Addr> = vec!["bob", "alice", "bob"]
+            .try_into_collect_with(deps.as_ref())
+            .unwrap();
+        assert_eq!(
+            set,
+            BTreeSet::from([Addr::unchecked("alice"), Addr::unchecked("bob")])
+        );
+
+        let res: StdResult<Vec<Addr>> =
+            vec!["alice".to_string(), "".to_string()].try_into_elementwise_with(deps.as_ref().api);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_try_into_elementwise_with_cw20_coins() {
+        let api = MockApi::default();
+        let cw20s = vec![Cw20Coin {
+            address: "apollo".to_string(),
+            amount: Uint128::new(10),
+        }];
+
+        let verified: Vec<Cw20CoinVerified> =
+            cw20s.try_into_elementwise_with(&api as &dyn Api).unwrap();
+        assert_eq!(
+            verified,
+            vec![Cw20CoinVerified {
+                address: Addr::unchecked("apollo"),
+                amount: Uint128::new(10),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_try_into_elementwise_with_asset_infos() {
+        let api = MockApi::default();
+        let infos = vec![
+            AssetInfoUnchecked::native("uosmo"),
+            AssetInfoUnchecked::cw20("apollo"),
+        ];
+
+        let checked: Vec<AssetInfo> = infos.try_into_elementwise_with(&api as &dyn Api).unwrap();
+        assert_eq!(
+            checked,
+            vec![
+                AssetInfo::native("uosmo"),
+                AssetInfo::cw20(Addr::unchecked("apollo")),
+            ]
+        );
+    }
 }
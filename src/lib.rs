@@ -2,5 +2,9 @@ pub mod assets;
 pub mod coins;
 pub mod iterators;
 pub mod macros;
+#[cfg(feature = "cw-storage-plus")]
+mod pagination;
 pub mod responses;
 pub mod submessages;
+#[cfg(feature = "stargate")]
+pub mod token_factory;
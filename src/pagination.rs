@@ -0,0 +1,8 @@
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Returns the number of items to return from a paginated query with the given
+/// `limit`, defaulting to 10 and capped at 30.
+pub(crate) fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize
+}
@@ -1,4 +1,10 @@
-use cosmwasm_std::Response;
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{
+    from_json, to_json_binary, BankMsg, Coin, CosmosMsg, ReplyOn, Response, StdResult, SubMsg,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
 
 /// Merge several Response objects into one. Currently ignores the data fields.
 ///
@@ -22,10 +28,145 @@ pub fn merge_responses(responses: Vec<Response>) -> Response {
     merged
 }
 
+/// Identifies messages that can be merged with each other.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum MergeKey {
+    BankSend {
+        to_address: String,
+    },
+    Cw20Transfer {
+        contract_addr: String,
+        recipient: String,
+    },
+}
+
+/// A message that can be merged, together with the amounts it transfers.
+struct Mergeable {
+    key: MergeKey,
+    amounts: Vec<Coin>,
+}
+
+fn as_mergeable(msg: &CosmosMsg) -> Option<Mergeable> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Some(Mergeable {
+            key: MergeKey::BankSend {
+                to_address: to_address.clone(),
+            },
+            amounts: amount.clone(),
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) if funds.is_empty() => match from_json(msg) {
+            Ok(Cw20ExecuteMsg::Transfer { recipient, amount }) => Some(Mergeable {
+                key: MergeKey::Cw20Transfer {
+                    contract_addr: contract_addr.clone(),
+                    recipient,
+                },
+                amounts: vec![Coin {
+                    denom: contract_addr.clone(),
+                    amount,
+                }],
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn merged_msg(key: MergeKey, amounts: BTreeMap<String, Uint128>) -> StdResult<CosmosMsg> {
+    Ok(match key {
+        MergeKey::BankSend { to_address } => BankMsg::Send {
+            to_address,
+            amount: amounts
+                .into_iter()
+                .map(|(denom, amount)| Coin { denom, amount })
+                .collect(),
+        }
+        .into(),
+        MergeKey::Cw20Transfer {
+            contract_addr,
+            recipient,
+        } => WasmMsg::Execute {
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient,
+                amount: amounts.into_values().sum(),
+            })?,
+            contract_addr,
+            funds: vec![],
+        }
+        .into(),
+    })
+}
+
+/// Merge all `BankMsg::Send`s to the same recipient, and all CW20 `Transfer`s
+/// of the same token to the same recipient, into one message each.
+///
+/// Each merged message takes the place of the last message it replaces, so
+/// that any funds produced by messages in between are available when it is
+/// executed. Messages that are not merged keep their relative order, and
+/// messages that have nothing to be merged with are returned unchanged. Only
+/// submessages without a reply or gas limit are merged.
+///
+/// Returns an error if a merged amount overflows.
+pub fn merge_transfer_submsgs(msgs: Vec<SubMsg>) -> StdResult<Vec<SubMsg>> {
+    let is_plain = |msg: &SubMsg| msg.reply_on == ReplyOn::Never && msg.gas_limit.is_none();
+    let mergeables = msgs
+        .iter()
+        .map(|msg| is_plain(msg).then(|| as_mergeable(&msg.msg)).flatten())
+        .collect::<Vec<_>>();
+
+    // Find the index of the last message, the number of messages and the total
+    // amounts per key
+    let mut groups: BTreeMap<&MergeKey, (usize, usize, BTreeMap<String, Uint128>)> =
+        BTreeMap::new();
+    for (idx, mergeable) in mergeables.iter().enumerate() {
+        if let Some(Mergeable { key, amounts }) = mergeable {
+            let (last_idx, count, totals) = groups.entry(key).or_default();
+            *last_idx = idx;
+            *count += 1;
+            for coin in amounts {
+                let total = totals.entry(coin.denom.clone()).or_default();
+                *total = total.checked_add(coin.amount)?;
+            }
+        }
+    }
+
+    let mut merged = Vec::with_capacity(msgs.len());
+    for (idx, (msg, mergeable)) in msgs.into_iter().zip(&mergeables).enumerate() {
+        match mergeable.as_ref().map(|m| (&m.key, &groups[&m.key])) {
+            None | Some((_, (_, 1, _))) => merged.push(msg),
+            Some((key, (last_idx, _, totals))) if *last_idx == idx => {
+                merged.push(SubMsg::new(merged_msg(key.clone(), totals.clone())?))
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(merged)
+}
+
+/// Same as [`merge_transfer_submsgs`], but for a `Vec<CosmosMsg>`.
+pub fn merge_transfer_msgs(msgs: Vec<CosmosMsg>) -> StdResult<Vec<CosmosMsg>> {
+    Ok(
+        merge_transfer_submsgs(msgs.into_iter().map(SubMsg::new).collect())?
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect(),
+    )
+}
+
+/// Same as [`merge_transfer_submsgs`], but merges the messages of a
+/// `Response`. All other fields of the `Response` are left unchanged.
+pub fn merge_response_transfers(mut response: Response) -> StdResult<Response> {
+    response.messages = merge_transfer_submsgs(response.messages)?;
+    Ok(response)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::{coins, BankMsg};
+    use cosmwasm_std::{coins, Empty};
 
     #[test]
     fn test_merge_empty_responses() {
@@ -82,4 +223,122 @@ mod tests {
         let resp2: Response = Response::new().set_data(b"data2");
         merge_responses(vec![resp1, resp2]);
     }
+
+    fn cw20_transfer(contract: &str, recipient: &str, amount: u128) -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    }
+
+    fn bank_send(to: &str, amount: Vec<Coin>) -> CosmosMsg {
+        BankMsg::Send {
+            to_address: to.to_string(),
+            amount,
+        }
+        .into()
+    }
+
+    fn other_msg(contract: &str) -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_json_binary(&Empty {}).unwrap(),
+            funds: vec![],
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_merge_transfer_msgs() {
+        let msgs = vec![
+            bank_send("alice", coins(1, "uosmo")),
+            other_msg("contract1"),
+            cw20_transfer("token", "alice", 10),
+            bank_send("bob", coins(1, "uosmo")),
+            bank_send("alice", vec![Coin::new(2, "uosmo"), Coin::new(3, "uatom")]),
+            other_msg("contract2"),
+            cw20_transfer("token", "alice", 20),
+            cw20_transfer("token", "bob", 5),
+            cw20_transfer("token2", "alice", 7),
+        ];
+
+        let merged = merge_transfer_msgs(msgs).unwrap();
+
+        assert_eq!(
+            merged,
+            vec![
+                other_msg("contract1"),
+                bank_send("bob", coins(1, "uosmo")),
+                bank_send("alice", vec![Coin::new(3, "uatom"), Coin::new(3, "uosmo")]),
+                other_msg("contract2"),
+                cw20_transfer("token", "alice", 30),
+                cw20_transfer("token", "bob", 5),
+                cw20_transfer("token2", "alice", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_transfer_msgs_nothing_to_merge() {
+        let msgs = vec![
+            bank_send("alice", vec![Coin::new(1, "uosmo"), Coin::new(1, "uatom")]),
+            other_msg("contract"),
+            cw20_transfer("token", "alice", 10),
+        ];
+
+        assert_eq!(merge_transfer_msgs(msgs.clone()).unwrap(), msgs);
+    }
+
+    #[test]
+    fn test_merge_transfer_msgs_ignores_cw20_with_funds() {
+        let with_funds: CosmosMsg = WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "alice".to_string(),
+                amount: Uint128::new(1),
+            })
+            .unwrap(),
+            funds: coins(1, "uosmo"),
+        }
+        .into();
+        let msgs = vec![
+            with_funds.clone(),
+            cw20_transfer("token", "alice", 1),
+            cw20_transfer("token", "alice", 2),
+        ];
+
+        assert_eq!(
+            merge_transfer_msgs(msgs).unwrap(),
+            vec![with_funds, cw20_transfer("token", "alice", 3)]
+        );
+    }
+
+    #[test]
+    fn test_merge_response_transfers_keeps_submsgs_with_reply() {
+        let response: Response = Response::new()
+            .add_attribute("key", "value")
+            .add_message(bank_send("alice", coins(1, "uosmo")))
+            .add_submessage(SubMsg::reply_on_success(
+                bank_send("alice", coins(2, "uosmo")),
+                1,
+            ))
+            .add_message(bank_send("alice", coins(3, "uosmo")));
+
+        let merged = merge_response_transfers(response).unwrap();
+
+        let expected: Response = Response::new()
+            .add_attribute("key", "value")
+            .add_submessage(SubMsg::reply_on_success(
+                bank_send("alice", coins(2, "uosmo")),
+                1,
+            ))
+            .add_message(bank_send("alice", coins(4, "uosmo")));
+        assert_eq!(merged, expected);
+    }
 }
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Coin, CosmosMsg, StdError, StdResult};
+
+use crate::coins::{encode_coin, encode_message_field, encode_string_field, validate_denom};
+
+/// The maximum length of a token factory subdenom, as in the Osmosis token
+/// factory module.
+pub const MAX_SUBDENOM_LENGTH: usize = 44;
+
+/// The maximum length of the creator address of a token factory denom, as in
+/// the Osmosis token factory module.
+pub const MAX_CREATOR_LENGTH: usize = 75;
+
+const TYPE_URL_PREFIX: &str = "/osmosis.tokenfactory.v1beta1";
+
+/// A token factory denom in format `factory/{creator}/{subdenom}`.
+#[cw_serde]
+pub struct TokenFactoryDenom {
+    pub creator: String,
+    pub subdenom: String,
+}
+
+impl TokenFactoryDenom {
+    /// Create a new `TokenFactoryDenom`.
+    ///
+    /// ### Errors
+    /// Returns an error if the creator is empty, longer than
+    /// [`MAX_CREATOR_LENGTH`] or contains a `/`.
+    /// Returns an error if the subdenom is longer than [`MAX_SUBDENOM_LENGTH`]
+    /// or contains characters not allowed in a denom.
+    /// Returns an error if the resulting denom is not valid according to
+    /// [`validate_denom`].
+    pub fn new(creator: impl Into<String>, subdenom: impl Into<String>) -> StdResult<Self> {
+        let denom = Self {
+            creator: creator.into(),
+            subdenom: subdenom.into(),
+        };
+        denom.validate()?;
+        Ok(denom)
+    }
+
+    fn validate(&self) -> StdResult<()> {
+        if self.creator.is_empty()
+            || self.creator.len() > MAX_CREATOR_LENGTH
+            || self.creator.contains('/')
+        {
+            return Err(StdError::generic_err(format!(
+                "Invalid token factory creator {}",
+                self.creator
+            )));
+        }
+        if self.subdenom.len() > MAX_SUBDENOM_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "Token factory subdenom {} is longer than {} characters",
+                self.subdenom, MAX_SUBDENOM_LENGTH
+            )));
+        }
+        if let Some(c) = self
+            .subdenom
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !"/:._-".contains(*c))
+        {
+            return Err(StdError::generic_err(format!(
+                "Invalid character {:?} in token factory subdenom {}",
+                c, self.subdenom
+            )));
+        }
+        validate_denom(&self.to_string())
+    }
+
+    /// Returns the full denom, i.e. `factory/{creator}/{subdenom}`.
+    pub fn denom(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for TokenFactoryDenom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "factory/{}/{}", self.creator, self.subdenom)
+    }
+}
+
+impl FromStr for TokenFactoryDenom {
+    type Err = StdError;
+
+    /// Parse a denom in format `factory/{creator}/{subdenom}`. The subdenom may
+    /// itself contain `/`.
+    fn from_str(denom: &str) -> StdResult<Self> {
+        match denom
+            .strip_prefix("factory/")
+            .and_then(|rest| rest.split_once('/'))
+        {
+            Some((creator, subdenom)) => Self::new(creator, subdenom),
+            None => Err(StdError::generic_err(format!(
+                "Invalid token factory denom {}: expected factory/{{creator}}/{{subdenom}}",
+                denom
+            ))),
+        }
+    }
+}
+
+/// Returns a `CosmosMsg::Stargate` for `MsgCreateDenom`, creating the denom
+/// `factory/{sender}/{subdenom}` with `sender` as admin.
+pub fn create_denom_msg(sender: &str, subdenom: &str) -> CosmosMsg {
+    let mut buf = Vec::new();
+    encode_string_field(&mut buf, 1, sender);
+    encode_string_field(&mut buf, 2, subdenom);
+    stargate_msg("MsgCreateDenom", buf)
+}
+
+/// Returns a `CosmosMsg::Stargate` for `MsgMint`, minting `amount` to
+/// `mint_to_address`. Only the admin of the denom can mint.
+pub fn mint_msg(sender: &str, amount: &Coin, mint_to_address: &str) -> CosmosMsg {
+    let mut buf = Vec::new();
+    encode_string_field(&mut buf, 1, sender);
+    encode_message_field(&mut buf, 2, &encode_coin(amount));
+    encode_string_field(&mut buf, 3, mint_to_address);
+    stargate_msg("MsgMint", buf)
+}
+
+/// Returns a `CosmosMsg::Stargate` for `MsgBurn`, burning `amount` from
+/// `burn_from_address`. Only the admin of the denom can burn.
+pub fn burn_msg(sender: &str, amount: &Coin, burn_from_address: &str) -> CosmosMsg {
+    let mut buf = Vec::new();
+    encode_string_field(&mut buf, 1, sender);
+    encode_message_field(&mut buf, 2, &encode_coin(amount));
+    encode_string_field(&mut buf, 3, burn_from_address);
+    stargate_msg("MsgBurn", buf)
+}
+
+/// Returns a `CosmosMsg::Stargate` for `MsgChangeAdmin`, transferring the
+/// admin rights of `denom` to `new_admin`.
+pub fn change_admin_msg(sender: &str, denom: &str, new_admin: &str) -> CosmosMsg {
+    let mut buf = Vec::new();
+    encode_string_field(&mut buf, 1, sender);
+    encode_string_field(&mut buf, 2, denom);
+    encode_string_field(&mut buf, 3, new_admin);
+    stargate_msg("MsgChangeAdmin", buf)
+}
+
+fn stargate_msg(msg_name: &str, value: Vec<u8>) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: format!("{}.{}", TYPE_URL_PREFIX, msg_name),
+        value: Binary(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CREATOR: &str = "osmo1g3kmqpp8608szfp0pdag3r6z85npph7wmccat8lgl3mp407kv73qlj7qwp";
+
+    #[test_case("osmo1abc", "vault" => Ok("factory/osmo1abc/vault".to_string()); "simple subdenom")]
+    #[test_case(CREATOR, "VaultToken/1/14d/ATOM/OSMO" => Ok(format!("factory/{}/VaultToken/1/14d/ATOM/OSMO", CREATOR)); "subdenom with slashes")]
+    #[test_case("osmo1abc", "" => Ok("factory/osmo1abc/".to_string()); "empty subdenom")]
+    #[test_case("", "vault" => Err(StdError::generic_err("Invalid token factory creator ")); "empty creator")]
+    #[test_case("osmo1/abc", "vault" => Err(StdError::generic_err("Invalid token factory creator osmo1/abc")); "creator with slash")]
+    #[test_case(&"a".repeat(76), "vault" => Err(StdError::generic_err(format!("Invalid token factory creator {}", "a".repeat(76)))); "creator too long")]
+    #[test_case("osmo1abc", &"a".repeat(45) => Err(StdError::generic_err(format!("Token factory subdenom {} is longer than 44 characters", "a".repeat(45)))); "subdenom too long")]
+    #[test_case("osmo1abc", "vault token" => Err(StdError::generic_err("Invalid character ' ' in token factory subdenom vault token")); "subdenom with space")]
+    fn test_token_factory_denom_new(creator: &str, subdenom: &str) -> StdResult<String> {
+        TokenFactoryDenom::new(creator, subdenom).map(|denom| denom.denom())
+    }
+
+    #[test_case("factory/osmo1abc/vault" => Ok(TokenFactoryDenom { creator: "osmo1abc".to_string(), subdenom: "vault".to_string() }); "simple subdenom")]
+    #[test_case("factory/osmo1abc/VaultToken/1" => Ok(TokenFactoryDenom { creator: "osmo1abc".to_string(), subdenom: "VaultToken/1".to_string() }); "subdenom with slashes")]
+    #[test_case("factory/osmo1abc" => Err(StdError::generic_err("Invalid token factory denom factory/osmo1abc: expected factory/{creator}/{subdenom}")); "missing subdenom")]
+    #[test_case("uosmo" => Err(StdError::generic_err("Invalid token factory denom uosmo: expected factory/{creator}/{subdenom}")); "not a token factory denom")]
+    fn test_token_factory_denom_from_str(denom: &str) -> StdResult<TokenFactoryDenom> {
+        denom.parse()
+    }
+
+    #[test]
+    fn test_create_denom_msg() {
+        assert_eq!(
+            create_denom_msg("osmo1abc", "vault"),
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgCreateDenom".to_string(),
+                value: Binary(b"\x0a\x08osmo1abc\x12\x05vault".to_vec()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_mint_msg() {
+        assert_eq!(
+            mint_msg(
+                "osmo1abc",
+                &Coin::new(1000, "factory/osmo1abc/vault"),
+                "osmo1def"
+            ),
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgMint".to_string(),
+                value: Binary(
+                    b"\x0a\x08osmo1abc\x12\x1e\x0a\x16factory/osmo1abc/vault\x12\x041000\x1a\x08osmo1def"
+                        .to_vec()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn test_burn_msg() {
+        assert_eq!(
+            burn_msg("osmo1abc", &Coin::new(5, "factory/osmo1abc/vault"), ""),
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgBurn".to_string(),
+                value: Binary(
+                    b"\x0a\x08osmo1abc\x12\x1b\x0a\x16factory/osmo1abc/vault\x12\x015".to_vec()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_admin_msg() {
+        assert_eq!(
+            change_admin_msg("osmo1abc", "factory/osmo1abc/vault", "osmo1def"),
+            CosmosMsg::Stargate {
+                type_url: "/osmosis.tokenfactory.v1beta1.MsgChangeAdmin".to_string(),
+                value: Binary(
+                    b"\x0a\x08osmo1abc\x12\x16factory/osmo1abc/vault\x1a\x08osmo1def".to_vec()
+                ),
+            }
+        );
+    }
+}